@@ -0,0 +1,144 @@
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use hello_rustaceans::qtree::*;
+use std::hint::black_box;
+
+const SIZES: [usize; 3] = [1_000, 100_000, 1_000_000];
+const WORLD: f32 = 10_000.0;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+fn uniform(n: usize) -> Vec<Vec2> {
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    (0..n)
+        .map(|_| vec2(rng.next(), rng.next()) * WORLD)
+        .collect()
+}
+
+fn clustered(n: usize) -> Vec<Vec2> {
+    let mut rng = Rng(0xD1B5_4A32_D192_ED03);
+    let centers: Vec<Vec2> = (0..16)
+        .map(|_| vec2(rng.next(), rng.next()) * WORLD)
+        .collect();
+    (0..n)
+        .map(|i| {
+            let spread = vec2(rng.next() + rng.next() - 1.0, rng.next() + rng.next() - 1.0);
+            centers[i % centers.len()] + spread * WORLD / 50.0
+        })
+        .collect()
+}
+
+fn datasets() -> impl Iterator<Item = (&'static str, usize, Vec<Vec2>)> {
+    SIZES
+        .into_iter()
+        .flat_map(|n| [("uniform", n, uniform(n)), ("clustered", n, clustered(n))])
+}
+
+fn empty_tree() -> QTreeMut<Vec2> {
+    QTreeMut::new(Square::new(0.0, 0.0, 1.0), Vec::new())
+}
+
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    group.sample_size(10);
+    for (name, n, values) in datasets() {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new(name, n), &values, |b, values| {
+            b.iter_batched(
+                empty_tree,
+                |mut tree| {
+                    for &value in values {
+                        tree.add(value);
+                    }
+                    tree
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn insert_far(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert_far");
+    group.sample_size(10);
+    for n in SIZES {
+        let offset = vec2(-1.0e6, 1.0e6);
+        let values: Vec<Vec2> = uniform(n).into_iter().map(|v| v + offset).collect();
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &values, |b, values| {
+            b.iter_batched(
+                empty_tree,
+                |mut tree| {
+                    for &value in values {
+                        tree.add(value);
+                    }
+                    tree
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("query");
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    let areas: Vec<Aabb> = (0..100)
+        .map(|_| {
+            let size = WORLD / 100.0;
+            Aabb::new(rng.next() * WORLD, rng.next() * WORLD, size, size)
+        })
+        .collect();
+    for (name, n, values) in datasets() {
+        let tree = QTreeMut::from_values(values);
+        group.bench_with_input(BenchmarkId::new(name, n), &tree, |b, tree| {
+            b.iter(|| {
+                let hits = areas.iter().map(|&area| tree.query(area).count());
+                black_box(hits.sum::<usize>())
+            })
+        });
+    }
+    group.finish();
+}
+
+fn serialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialize");
+    group.sample_size(10);
+    for n in SIZES {
+        let tree = QTreeMut::from_values(uniform(n));
+        let mut bytes = Vec::new();
+        tree.write_binary(&mut bytes).unwrap();
+        let json = serde_json::to_vec(&tree).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("write_binary", n), &tree, |b, tree| {
+            b.iter(|| {
+                let mut out = Vec::with_capacity(bytes.len());
+                tree.write_binary(&mut out).unwrap();
+                out
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("read_binary", n), &bytes, |b, bytes| {
+            b.iter(|| QTreeMut::<Vec2>::read_binary(bytes.as_slice()).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("to_json", n), &tree, |b, tree| {
+            b.iter(|| serde_json::to_vec(tree).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("from_json", n), &json, |b, json| {
+            b.iter(|| serde_json::from_slice::<QTreeMut<Vec2>>(json).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, insert, insert_far, query, serialize);
+criterion_main!(benches);
@@ -0,0 +1,268 @@
+use crate::qtree::*;
+use std::collections::BTreeMap;
+use std::{fmt, fs, io, path::Path};
+
+#[derive(Debug)]
+pub enum AnimationError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    UnknownClip(String),
+    EmptyClip(String),
+    Duration(String),
+    MissingClip(String),
+}
+
+impl fmt::Display for AnimationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnimationError::Io(e) => write!(f, "animation io error: {e}"),
+            AnimationError::Json(e) => write!(f, "animation format error: {e}"),
+            AnimationError::UnknownClip(name) => write!(f, "mirror of unknown clip {name:?}"),
+            AnimationError::EmptyClip(name) => write!(f, "clip {name:?} has no frames"),
+            AnimationError::Duration(name) => write!(f, "clip {name:?} has a frame without time"),
+            AnimationError::MissingClip(name) => write!(f, "clip {name:?} is missing"),
+        }
+    }
+}
+
+impl std::error::Error for AnimationError {}
+
+impl From<io::Error> for AnimationError {
+    fn from(e: io::Error) -> Self {
+        AnimationError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for AnimationError {
+    fn from(e: serde_json::Error) -> Self {
+        AnimationError::Json(e)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoopMode {
+    #[default]
+    Loop,
+    PingPong,
+    Once,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Frame {
+    pub rect: Aabb,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f32>,
+}
+
+fn default_duration() -> f32 {
+    0.5
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Clip {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub frames: Vec<Frame>,
+    #[serde(default = "default_duration")]
+    pub duration: f32,
+    #[serde(default)]
+    pub mode: LoopMode,
+    #[serde(default)]
+    pub flip_x: bool,
+    // reuse another clip's frames, flipped horizontally
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<String>,
+}
+
+impl Clip {
+    pub fn frame_duration(&self, frame: usize) -> f32 {
+        self.frames[frame].duration.unwrap_or(self.duration)
+    }
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+// deserializing goes through resolve, so every set in hand has usable clips
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(try_from = "RawAnimationSet")]
+pub struct AnimationSet {
+    pub image: String,
+    pub scale: f32,
+    pub clips: BTreeMap<String, Clip>,
+}
+
+#[derive(Deserialize)]
+struct RawAnimationSet {
+    image: String,
+    #[serde(default = "default_scale")]
+    scale: f32,
+    clips: BTreeMap<String, Clip>,
+}
+
+impl TryFrom<RawAnimationSet> for AnimationSet {
+    type Error = AnimationError;
+
+    fn try_from(raw: RawAnimationSet) -> Result<Self, AnimationError> {
+        let mut set = AnimationSet {
+            image: raw.image,
+            scale: raw.scale,
+            clips: raw.clips,
+        };
+        set.resolve()?;
+        Ok(set)
+    }
+}
+
+impl AnimationSet {
+    pub fn from_json(json: &str) -> Result<Self, AnimationError> {
+        // kept apart from the serde path so clip errors keep their own variants
+        let raw: RawAnimationSet = serde_json::from_str(json)?;
+        Self::try_from(raw)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, AnimationError> {
+        let path = path.as_ref();
+        let mut set = Self::from_json(&fs::read_to_string(path)?)?;
+        if let Some(dir) = path.parent() {
+            set.image = dir.join(&set.image).to_string_lossy().into_owned();
+        }
+        Ok(set)
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&Clip> {
+        self.clips.get(name)
+    }
+
+    fn resolve(&mut self) -> Result<(), AnimationError> {
+        let mirrors: Vec<(String, String)> = self
+            .clips
+            .iter()
+            .filter_map(|(name, clip)| Some((name.clone(), clip.mirror.clone()?)))
+            .collect();
+        for (name, source) in mirrors {
+            let source = match self.clips.get(&source) {
+                Some(clip) if clip.mirror.is_none() => clip.clone(),
+                _ => return Err(AnimationError::UnknownClip(source)),
+            };
+            let clip = self.clips.get_mut(&name).unwrap();
+            clip.frames = source.frames;
+            clip.duration = source.duration;
+            clip.mode = source.mode;
+            clip.flip_x = !source.flip_x;
+        }
+        for (name, clip) in &self.clips {
+            if clip.frames.is_empty() {
+                return Err(AnimationError::EmptyClip(name.clone()));
+            }
+            // a zero duration would never let the clock move past its frame
+            if (0..clip.frames.len()).any(|i| clip.frame_duration(i) <= 0.0) {
+                return Err(AnimationError::Duration(name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationEvent {
+    Looped,
+    Finished,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Animation {
+    clip: String,
+    frame: usize,
+    elapsed: f32,
+    reverse: bool,
+    finished: bool,
+}
+
+impl Animation {
+    pub fn new(clip: &str) -> Self {
+        Animation {
+            clip: clip.to_owned(),
+            frame: 0,
+            elapsed: 0.0,
+            reverse: false,
+            finished: false,
+        }
+    }
+
+    pub fn clip(&self) -> &str {
+        &self.clip
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            *self = Self::new(clip);
+        }
+    }
+
+    pub fn update(&mut self, dt: f32, set: &AnimationSet) -> Option<AnimationEvent> {
+        let clip = set.clip(&self.clip)?;
+        if self.finished {
+            return None;
+        }
+        self.elapsed += dt;
+        let mut event = None;
+        while self.elapsed >= clip.frame_duration(self.frame) {
+            self.elapsed -= clip.frame_duration(self.frame);
+            event = self.step(set).or(event);
+            if self.finished {
+                self.elapsed = 0.0;
+                break;
+            }
+        }
+        event
+    }
+
+    pub fn step(&mut self, set: &AnimationSet) -> Option<AnimationEvent> {
+        let clip = set.clip(&self.clip)?;
+        let last = clip.frames.len() - 1;
+        if self.finished {
+            return None;
+        }
+        match clip.mode {
+            LoopMode::Loop if self.frame == last => {
+                self.frame = 0;
+                return Some(AnimationEvent::Looped);
+            }
+            LoopMode::Once if self.frame == last => {
+                self.finished = true;
+                return Some(AnimationEvent::Finished);
+            }
+            LoopMode::Loop | LoopMode::Once => self.frame += 1,
+            LoopMode::PingPong => {
+                if self.frame == last && !self.reverse || self.frame == 0 && self.reverse {
+                    self.reverse = !self.reverse;
+                }
+                self.frame = match (last, self.reverse) {
+                    (0, _) => 0,
+                    (_, false) => self.frame + 1,
+                    (_, true) => self.frame - 1,
+                };
+                // a ping-pong cycle ends when it comes back to the first frame
+                if self.frame == 0 {
+                    return Some(AnimationEvent::Looped);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn current<'a>(&self, set: &'a AnimationSet) -> Option<(&'a Frame, bool)> {
+        let clip = set.clip(&self.clip)?;
+        Some((clip.frames.get(self.frame)?, clip.flip_x))
+    }
+}
@@ -1,3 +1,4 @@
+use hello_rustaceans::map::*;
 use hello_rustaceans::qtree::*;
 use hello_rustaceans::world::*;
 
@@ -8,26 +9,39 @@ async fn main() {
     let mut screen = Screen::new();
 
     let region = Rect::new(-CELL / 2.0, -CELL / 2.0, CELL, CELL);
-    let mut quadtree: QTreeMut<Item> = QTreeMut::new(region.into(), vec![]);
+    let config = QTreeConfig {
+        min_size: GRID,
+        ..Default::default()
+    };
+    let mut quadtree: QTreeMut<Item> = QTreeMut::with_config(region.into(), vec![], config);
+    let mut meta = MapMeta::default();
 
     // let tools = ();
 
     let mut click_value: Option<Item> = None;
+    let mut show_stats = false;
 
     loop {
         let mouse_pos = Vec2::from(mouse_position());
         let world_click = screen.world_pos(mouse_pos);
-        let grid_knot = (world_click / GRID).round() * GRID;
+        let mut grid_knot = (world_click / GRID).round() * GRID;
+        if is_key_down(KeyCode::LeftShift) {
+            grid_knot = quadtree.closest_corner(world_click).unwrap_or(grid_knot);
+        }
         if is_mouse_button_pressed(MouseButton::Left) {
             if let Some(item) = click_value {
-                let wh = grid_knot - item.rect.point();
-                let value = Rect::new(item.rect.x, item.rect.y, wh.x, wh.y);
+                let min = item.rect.point().min(grid_knot);
+                let wh = item.rect.point().max(grid_knot) - min;
+                let value = Rect::new(min.x, min.y, wh.x, wh.y);
                 quadtree.add(value.into());
                 click_value = None;
             } else {
                 click_value = Some(Item::new(grid_knot))
             }
         }
+        if is_mouse_button_pressed(MouseButton::Right) && click_value.take().is_none() {
+            quadtree.remove_where(|item| item.rect.contains(world_click));
+        }
 
         if is_key_down(KeyCode::D) {
             screen.target.x += 10.0 / screen.scale;
@@ -68,6 +82,13 @@ async fn main() {
 
         set_default_camera();
 
+        if show_stats {
+            let stats = quadtree.stats().to_string();
+            for (i, line) in stats.lines().enumerate() {
+                draw_text(line, 10.0, 24.0 + 20.0 * i as f32, 20.0, WHITE);
+            }
+        }
+
         // tools
 
         match get_last_key_pressed() {
@@ -77,6 +98,19 @@ async fn main() {
             Some(KeyCode::E) => {
                 screen.scale /= 1.2;
             }
+            Some(KeyCode::F3) => {
+                show_stats = !show_stats;
+            }
+            Some(KeyCode::F5) => {
+                let map = MapFile::new(meta.clone(), quadtree.clone());
+                if let Err(e) = map.save(MAP_PATH) {
+                    eprintln!("{e}");
+                }
+            }
+            Some(KeyCode::F9) => match MapFile::load(MAP_PATH) {
+                Ok(map) => (meta, quadtree) = (map.meta, map.tree),
+                Err(e) => eprintln!("{e}"),
+            },
             _ => {}
         }
 
@@ -1,3 +1,10 @@
+pub mod animation;
+#[cfg(feature = "render")]
+pub mod map;
+pub mod physics;
+#[cfg(feature = "render")]
 pub mod player;
 pub mod qtree;
+pub mod state;
+#[cfg(feature = "render")]
 pub mod world;
@@ -1,12 +1,17 @@
+use hello_rustaceans::map::*;
+use hello_rustaceans::physics::*;
 use hello_rustaceans::player::*;
 use hello_rustaceans::qtree::*;
 use hello_rustaceans::world::*;
 
+use std::rc::Rc;
 use std::time;
 
+// longer frames are split up so a stall can't tunnel the player through geometry
+const MAX_DT: f32 = 1.0 / 30.0;
+
 #[macroquad::main("Platformer")]
 async fn main() {
-    let mut instant = time::Instant::now();
     if let Ok(n) = time::SystemTime::now().duration_since(time::SystemTime::UNIX_EPOCH) {
         rand::srand(n.as_secs());
     }
@@ -15,38 +20,41 @@ async fn main() {
     let mut screen = Screen::new();
     let zoom = Vec2::ONE / screen.center;
 
-    let sprite =
-        Texture2D::from_file_with_format(include_bytes!("../resources/player_sprite.png"), None);
+    let animations = Rc::new(load_animations());
+    let sprite = match load_texture(&animations.image).await {
+        Ok(texture) => texture,
+        Err(e) => {
+            eprintln!("{e}");
+            let bytes = include_bytes!("../resources/player_sprite.png");
+            Texture2D::from_file_with_format(bytes, None)
+        }
+    };
     sprite.set_filter(FilterMode::Nearest);
 
-    let mut position = screen.target;
-    let mut direction = Dir::Right;
-    let mut player_move = PlayerState::Idle;
-
-    let mut animation = PlayerAnimation::new(player_move, direction);
+    let world = match MapFile::load(MAP_PATH) {
+        Ok(map) => map.tree,
+        Err(e) => {
+            eprintln!("{e}");
+            let region = Rect::new(-CELL / 2.0, -CELL / 2.0, CELL, CELL);
+            QTreeMut::new(region.into(), Vec::new())
+        }
+    };
+    let mut player = Player::new(sprite, animations, screen.target);
 
     loop {
-        if is_key_down(KeyCode::D) {
-            direction = Dir::Right;
-        }
-        if is_key_down(KeyCode::A) {
-            direction = Dir::Left;
-        }
-        if is_key_down(KeyCode::S) {
-            direction = Dir::Down;
-        }
-        if is_key_down(KeyCode::W) {
-            direction = Dir::Up;
+        let mut input = Input::from_keyboard();
+
+        let mut dt = get_frame_time();
+        while dt > 0.0 {
+            player.update(dt.min(MAX_DT), &input, &world);
+            input.jump_pressed = false;
+            input.interact = false;
+            dt -= MAX_DT;
         }
+        screen.target = player.pos();
 
         // let map_coords = target.coords(CELL);
 
-        if instant.elapsed().as_millis() > TIMESTEP {
-            instant = time::Instant::now();
-
-            animation.update(player_move, direction);
-        }
-
         let camera = Camera2D {
             target: screen.target,
             zoom,
@@ -57,7 +65,9 @@ async fn main() {
 
         clear_background(DARKGRAY);
 
-        animation.draw(&sprite, position);
+        world.draw(screen.scale, screen.world_rec_to_render());
+
+        player.draw();
 
         screen.draw_grid();
 
@@ -0,0 +1,128 @@
+use crate::qtree::*;
+use crate::world::*;
+use serde_json::{Value, json};
+use std::{fmt, fs, io, path::Path};
+
+pub const MAP_VERSION: u32 = 1;
+pub const MAP_PATH: &str = "map.json";
+
+type Migration = fn(Value) -> Value;
+
+// MIGRATIONS[n] upgrades a version n document to version n + 1
+const MIGRATIONS: [Migration; MAP_VERSION as usize] = [wrap_bare_tree];
+
+fn wrap_bare_tree(tree: Value) -> Value {
+    json!({
+        "version": 1,
+        "meta": MapMeta::default(),
+        "tree": tree,
+    })
+}
+
+#[derive(Debug)]
+pub enum MapError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Version(u32),
+    BadVersion(Value),
+    Tree(String),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::Io(e) => write!(f, "map io error: {e}"),
+            MapError::Json(e) => write!(f, "map format error: {e}"),
+            MapError::Version(v) => write!(f, "map version {v} is newer than {MAP_VERSION}"),
+            MapError::BadVersion(v) => write!(f, "map version {v} is not a version number"),
+            MapError::Tree(e) => write!(f, "map tree is malformed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<io::Error> for MapError {
+    fn from(e: io::Error) -> Self {
+        MapError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for MapError {
+    fn from(e: serde_json::Error) -> Self {
+        MapError::Json(e)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MapMeta {
+    pub name: String,
+    pub author: String,
+    pub grid: f32,
+    pub cell: f32,
+}
+
+impl Default for MapMeta {
+    fn default() -> Self {
+        MapMeta {
+            name: String::from("untitled"),
+            author: String::new(),
+            grid: GRID,
+            cell: CELL,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MapFile {
+    pub version: u32,
+    pub meta: MapMeta,
+    pub tree: QTreeMut<Item>,
+}
+
+impl MapFile {
+    pub fn new(meta: MapMeta, tree: QTreeMut<Item>) -> Self {
+        MapFile {
+            version: MAP_VERSION,
+            meta,
+            tree,
+        }
+    }
+
+    pub fn migrate(mut doc: Value) -> Result<Value, MapError> {
+        // only documents from before versioning lack the field entirely
+        let version = match doc.get("version") {
+            None => 0,
+            Some(v) => v
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| MapError::BadVersion(v.clone()))?,
+        };
+        if version > MAP_VERSION {
+            return Err(MapError::Version(version));
+        }
+        for migration in &MIGRATIONS[version as usize..] {
+            doc = migration(doc);
+        }
+        Ok(doc)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, MapError> {
+        let doc = Self::migrate(serde_json::from_str(json)?)?;
+        let map: MapFile = serde_json::from_value(doc)?;
+        map.tree.validate().map_err(MapError::Tree)?;
+        Ok(map)
+    }
+
+    pub fn to_json(&self) -> Result<String, MapError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, MapError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), MapError> {
+        Ok(fs::write(path, self.to_json()?)?)
+    }
+}
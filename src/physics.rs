@@ -0,0 +1,156 @@
+use crate::qtree::*;
+
+// tolerance in world units for resting contact, so touching surfaces still block
+const CONTACT: f32 = 0.01;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhysicsConfig {
+    pub gravity: f32,
+    pub max_fall: f32,
+    pub run_accel: f32,
+    pub air_accel: f32,
+    pub friction: f32,
+    pub max_run: f32,
+    pub jump_speed: f32,
+    // extra gravity while rising with jump released, giving variable jump height
+    pub release_gravity: f32,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        PhysicsConfig {
+            gravity: 2400.0,
+            max_fall: 1400.0,
+            run_accel: 3200.0,
+            air_accel: 1600.0,
+            friction: 2800.0,
+            max_run: 420.0,
+            jump_speed: 900.0,
+            release_gravity: 3.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Input {
+    pub move_x: f32,
+    pub move_y: f32,
+    pub jump_pressed: bool,
+    pub jump_held: bool,
+    pub interact: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Body {
+    pub pos: Vec2,
+    pub half: Vec2,
+    pub vel: Vec2,
+    pub on_ground: bool,
+}
+
+impl Positioned for Body {
+    type Scalar = f32;
+
+    fn pos(&self) -> Vec2 {
+        self.pos
+    }
+
+    fn bounds(&self) -> Aabb {
+        let corner = self.pos - self.half;
+        Aabb::new(corner.x, corner.y, self.half.x * 2.0, self.half.y * 2.0)
+    }
+}
+
+fn approach(value: f32, target: f32, step: f32) -> f32 {
+    if value < target {
+        (value + step).min(target)
+    } else {
+        (value - step).max(target)
+    }
+}
+
+impl Body {
+    pub fn new(pos: Vec2, size: Vec2) -> Self {
+        Body {
+            pos,
+            half: size / 2.0,
+            vel: Vec2::ZERO,
+            on_ground: false,
+        }
+    }
+
+    pub fn step<T>(&mut self, dt: f32, input: &Input, config: &PhysicsConfig, world: &QTreeMut<T>)
+    where
+        T: Clone + Positioned<Scalar = f32>,
+    {
+        let move_x = input.move_x.clamp(-1.0, 1.0);
+        let accel = if self.on_ground {
+            config.run_accel
+        } else {
+            config.air_accel
+        };
+        self.vel.x = if move_x != 0.0 {
+            approach(self.vel.x, move_x * config.max_run, accel * dt)
+        } else if self.on_ground {
+            approach(self.vel.x, 0.0, config.friction * dt)
+        } else {
+            self.vel.x
+        };
+
+        if input.jump_pressed && self.on_ground {
+            self.vel.y = -config.jump_speed;
+        }
+        let gravity = if self.vel.y < 0.0 && !input.jump_held {
+            config.gravity * config.release_gravity
+        } else {
+            config.gravity
+        };
+        self.vel.y = (self.vel.y + gravity * dt).min(config.max_fall);
+
+        let delta = self.vel * dt;
+        let bounds = self.bounds();
+        let swept = Aabb::new(
+            bounds.x - delta.x.abs(),
+            bounds.y - delta.y.abs(),
+            bounds.w + delta.x.abs() * 2.0,
+            bounds.h + delta.y.abs() * 2.0,
+        );
+        let solids: Vec<Aabb> = world.query(swept).map(Positioned::bounds).collect();
+
+        if self.sweep(0, delta.x, &solids) {
+            self.vel.x = 0.0;
+        }
+        self.on_ground = false;
+        if self.sweep(1, delta.y, &solids) {
+            self.on_ground = delta.y > 0.0;
+            self.vel.y = 0.0;
+        }
+    }
+
+    // moves along one axis as far as the solids allow, returning whether it was stopped
+    fn sweep(&mut self, axis: usize, delta: f32, solids: &[Aabb]) -> bool {
+        let span = |rect: &Aabb, axis: usize| match axis {
+            0 => (rect.x, rect.right()),
+            _ => (rect.y, rect.bottom()),
+        };
+        let bounds = self.bounds();
+        let (lo, hi) = span(&bounds, axis);
+        let (cross_lo, cross_hi) = span(&bounds, 1 - axis);
+
+        let mut allowed = delta;
+        for solid in solids {
+            let (solid_lo, solid_hi) = span(solid, axis);
+            let (other_lo, other_hi) = span(solid, 1 - axis);
+            if cross_hi <= other_lo + CONTACT || cross_lo >= other_hi - CONTACT {
+                continue;
+            }
+            if delta > 0.0 && solid_lo >= hi - CONTACT {
+                allowed = allowed.min((solid_lo - hi).max(0.0));
+            } else if delta < 0.0 && solid_hi <= lo + CONTACT {
+                allowed = allowed.max((solid_hi - lo).min(0.0));
+            }
+        }
+        self.pos[axis] += allowed;
+        allowed != delta
+    }
+}
@@ -1,30 +1,46 @@
+use crate::animation::*;
+use crate::physics::*;
 use crate::qtree::*;
-use macroquad::experimental::animation::*;
+use crate::state::*;
 use macroquad::prelude::*;
-
-pub const TIMESTEP: u128 = 500; // ms
+use std::rc::Rc;
 
 pub const SPRITE: f32 = 16.0;
-pub const FRAMES: usize = 2;
 
 pub const SCALE: f32 = 4.0;
 pub const UNIT: f32 = SPRITE * SCALE;
 
 pub const SIZE: Vec2 = Vec2::splat(UNIT);
-const HALF_SIZE: Vec2 = Vec2::splat(UNIT / 2.0);
 
-pub const fn get_source_rect(x: usize, y: usize) -> Rect {
-    Rect::new(SPRITE * x as f32, SPRITE * y as f32, SPRITE, SPRITE)
+pub const ANIMATIONS_PATH: &str = "resources/player.json";
+const ANIMATIONS: &str = include_str!("../resources/player.json");
+
+pub fn load_animations() -> AnimationSet {
+    let checked = |set: AnimationSet| check_clips(&set).map(|()| set);
+    AnimationSet::load(ANIMATIONS_PATH)
+        .and_then(checked)
+        .unwrap_or_else(|e| {
+            eprintln!("{e}");
+            let set = AnimationSet::from_json(ANIMATIONS).and_then(checked);
+            set.expect("embedded player animations")
+        })
 }
 
-#[derive(Copy, Clone, PartialEq)]
-pub enum PlayerState {
-    Sit,
-    Hold,
-    Idle,
-    Walk,
-    Hit,
-    Raise,
+pub fn clip_name(state: PlayerState, dir: Dir) -> String {
+    format!("{}_{}", state.name(), dir.name())
+}
+
+// a missing clip would otherwise only show up as a blank sprite in that state
+pub fn check_clips(set: &AnimationSet) -> Result<(), AnimationError> {
+    for state in PlayerState::ALL {
+        for dir in Dir::ALL {
+            let name = clip_name(state, dir);
+            if set.clip(&name).is_none() {
+                return Err(AnimationError::MissingClip(name));
+            }
+        }
+    }
+    Ok(())
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -35,61 +51,171 @@ pub enum Dir {
     Right,
 }
 
-struct Player {
-    image: Texture2D,
-    sprite: AnimatedSprite,
-    state: PlayerState,
+impl Dir {
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Down, Dir::Left, Dir::Right];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Dir::Up => "up",
+            Dir::Down => "down",
+            Dir::Left => "left",
+            Dir::Right => "right",
+        }
+    }
+}
+
+pub struct Player {
+    pub body: Body,
+    pub physics: PhysicsConfig,
+    sprite: Texture2D,
+    animation: PlayerAnimation,
+    machine: StateMachine,
+    carrying: bool,
     dir: Dir,
-    pos: Vec2,
 }
 
 impl Positioned for Player {
+    type Scalar = f32;
+
     fn pos(&self) -> Vec2 {
-        self.pos
+        self.body.pos
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.body.bounds()
+    }
+}
+
+impl Player {
+    pub fn new(sprite: Texture2D, animations: Rc<AnimationSet>, pos: Vec2) -> Self {
+        let (state, dir) = (PlayerState::Idle, Dir::Right);
+        Player {
+            body: Body::new(pos, SIZE),
+            physics: PhysicsConfig::default(),
+            sprite,
+            animation: PlayerAnimation::new(animations, state, dir),
+            machine: StateMachine::new(state),
+            carrying: false,
+            dir,
+        }
+    }
+
+    pub fn state(&self) -> PlayerState {
+        self.machine.state()
+    }
+
+    pub fn carrying(&self) -> bool {
+        self.carrying
+    }
+
+    pub fn hit(&mut self) {
+        self.machine.hurt(HIT_STUN);
+    }
+
+    pub fn dir(&self) -> Dir {
+        self.dir
+    }
+
+    pub fn update<T>(&mut self, dt: f32, input: &Input, world: &QTreeMut<T>)
+    where
+        T: Clone + Positioned<Scalar = f32>,
+    {
+        if input.move_x > 0.0 {
+            self.dir = Dir::Right;
+        }
+        if input.move_x < 0.0 {
+            self.dir = Dir::Left;
+        }
+        if input.move_y > 0.0 {
+            self.dir = Dir::Down;
+        }
+        if input.move_y < 0.0 {
+            self.dir = Dir::Up;
+        }
+        if input.interact {
+            self.carrying = !self.carrying;
+        }
+
+        let mut input = *input;
+        if matches!(self.state(), PlayerState::Sit | PlayerState::Hit) {
+            input.move_x = 0.0;
+            input.jump_pressed = false;
+        }
+        self.body.step(dt, &input, &self.physics, world);
+
+        let event = self.animation.update(dt, self.state(), self.dir);
+        let signals = Signals {
+            moving: self.body.vel.x != 0.0,
+            crouching: input.move_y > 0.0,
+            carrying: self.carrying,
+            on_ground: self.body.on_ground,
+            clip_finished: event == Some(AnimationEvent::Finished),
+        };
+        self.machine.update(dt, &signals, &mut self.animation);
+    }
+
+    pub fn draw(&self) {
+        self.animation.draw(&self.sprite, self.body.pos);
+    }
+}
+
+impl Input {
+    pub fn from_keyboard() -> Self {
+        let axis = |neg: KeyCode, pos: KeyCode| {
+            is_key_down(pos) as i32 as f32 - is_key_down(neg) as i32 as f32
+        };
+        Input {
+            move_x: axis(KeyCode::A, KeyCode::D),
+            move_y: axis(KeyCode::W, KeyCode::S),
+            jump_pressed: is_key_pressed(KeyCode::Space),
+            jump_held: is_key_down(KeyCode::Space),
+            interact: is_key_pressed(KeyCode::E),
+        }
     }
 }
 
 pub struct PlayerAnimation {
+    set: Rc<AnimationSet>,
     state: PlayerState,
     dir: Dir,
-    frame: usize,
+    animation: Animation,
 }
 
 impl PlayerAnimation {
-    pub fn new(state: PlayerState, dir: Dir) -> PlayerAnimation {
+    pub fn new(set: Rc<AnimationSet>, state: PlayerState, dir: Dir) -> PlayerAnimation {
         PlayerAnimation {
+            set,
             state,
             dir,
-            frame: 0,
+            animation: Animation::new(&clip_name(state, dir)),
         }
     }
 
-    fn step(&mut self) {
-        self.frame = (self.frame + 1) % FRAMES;
-    }
-
-    pub fn update(&mut self, state: PlayerState, dir: Dir) {
+    pub fn update(&mut self, dt: f32, state: PlayerState, dir: Dir) -> Option<AnimationEvent> {
         if self.state != state || self.dir != dir {
-            *self = Self::new(state, dir);
-        } else {
-            self.step();
+            *self = Self::new(self.set.clone(), state, dir);
         }
+        self.animation.update(dt, &self.set)
     }
 
     pub fn draw(&self, sprite: &Texture2D, pos: Vec2) {
-        let pos = pos - HALF_SIZE;
-        let column = self.frame
-            + match self.dir {
-                Dir::Up => 2,
-                Dir::Down => 0,
-                Dir::Left | Dir::Right => 4,
-            };
+        let Some((frame, flip_x)) = self.animation.current(&self.set) else {
+            return;
+        };
+        let size = vec2(frame.rect.w, frame.rect.h) * self.set.scale;
+        let pos = pos - size / 2.0;
         let params = DrawTextureParams {
-            dest_size: Some(SIZE),
-            source: Some(get_source_rect(column, self.state as usize)),
-            flip_x: self.dir == Dir::Left,
+            dest_size: Some(size),
+            source: Some(frame.rect.into()),
+            flip_x,
             ..Default::default()
         };
         draw_texture_ex(sprite, pos.x, pos.y, WHITE, params);
     }
 }
+
+impl StateHooks for PlayerAnimation {
+    fn enter(&mut self, state: PlayerState) {
+        *self = Self::new(self.set.clone(), state, self.dir);
+    }
+}
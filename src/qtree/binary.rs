@@ -0,0 +1,210 @@
+use super::*;
+use serde::de::DeserializeOwned;
+use std::io::{self, Read, Write};
+
+pub const BINARY_MAGIC: [u8; 4] = *b"QTRB";
+pub const BINARY_VERSION: u16 = 3;
+
+// header counts are untrusted until the checksum at the end has been read
+const MAX_RESERVE: u32 = 1 << 12;
+
+struct Checksum<S> {
+    inner: S,
+    hasher: crc32fast::Hasher,
+}
+
+impl<S> Checksum<S> {
+    fn new(inner: S) -> Self {
+        Checksum {
+            inner,
+            hasher: crc32fast::Hasher::new(),
+        }
+    }
+
+    fn value(&self) -> u32 {
+        self.hasher.clone().finalize()
+    }
+}
+
+impl<R: Read> Read for Checksum<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<W: Write> Write for Checksum<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn invalid<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    read_array(reader).map(u32::from_le_bytes)
+}
+
+fn read_scalar<S: Scalar>(reader: &mut impl Read) -> io::Result<S> {
+    bincode::deserialize_from(reader).map_err(invalid)
+}
+
+fn write_scalar<S: Scalar>(writer: &mut impl Write, value: S) -> io::Result<()> {
+    bincode::serialize_into(writer, &value).map_err(invalid)
+}
+
+impl<T: Clone + Positioned> QTree<T> {
+    pub fn write_binary<W: Write>(&self, writer: W) -> io::Result<()>
+    where
+        T: Serialize,
+    {
+        let mut writer = Checksum::new(writer);
+        writer.write_all(&BINARY_MAGIC)?;
+        writer.write_all(&BINARY_VERSION.to_le_bytes())?;
+        writer.write_all(&[T::Scalar::TAG])?;
+
+        writer.write_all(&(self.config.capacity as u32).to_le_bytes())?;
+        write_scalar(&mut writer, self.config.min_size)?;
+        writer.write_all(&(self.config.max_depth as u32).to_le_bytes())?;
+        writer.write_all(&[self.base.is_some() as u8])?;
+        if let Some(base) = self.base {
+            for s in [base.x, base.y, base.w] {
+                write_scalar(&mut writer, s)?;
+            }
+        }
+        writer.write_all(&(self.nodes.len() as u32).to_le_bytes())?;
+        writer.write_all(&(self.values.len() as u32).to_le_bytes())?;
+
+        for node in &self.nodes {
+            let region = node.region;
+            for s in [region.x, region.y, region.w] {
+                write_scalar(&mut writer, s)?;
+            }
+            writer.write_all(&node.next.to_le_bytes())?;
+            writer.write_all(&[node.leaf as u8])?;
+            writer.write_all(&node.start.to_le_bytes())?;
+            writer.write_all(&node.end.to_le_bytes())?;
+        }
+
+        let mut blob = Vec::new();
+        for value in &self.values {
+            blob.clear();
+            bincode::serialize_into(&mut blob, value).map_err(invalid)?;
+            writer.write_all(&(blob.len() as u32).to_le_bytes())?;
+            writer.write_all(&blob)?;
+        }
+
+        let checksum = writer.value();
+        writer.inner.write_all(&checksum.to_le_bytes())?;
+        writer.flush()
+    }
+
+    pub fn read_binary<R: Read>(reader: R) -> io::Result<Self>
+    where
+        T: DeserializeOwned,
+    {
+        let mut reader = Checksum::new(reader);
+        if read_array(&mut reader)? != BINARY_MAGIC {
+            return Err(invalid("not a quadtree binary"));
+        }
+        let version = u16::from_le_bytes(read_array(&mut reader)?);
+        // version 1 predates the scalar tag and always stored f32 coordinates
+        let tag = match version {
+            1 => 0,
+            2 | BINARY_VERSION => read_array::<1>(&mut reader)?[0],
+            _ => return Err(invalid(format!("unsupported binary version {version}"))),
+        };
+        if tag != T::Scalar::TAG {
+            return Err(invalid(format!("unexpected scalar tag {tag}")));
+        }
+
+        let config = QTreeConfig {
+            capacity: read_u32(&mut reader)? as usize,
+            min_size: read_scalar(&mut reader)?,
+            max_depth: read_u32(&mut reader)? as usize,
+        };
+        // versions before 3 did not record the construction region
+        let base = if version == BINARY_VERSION && read_array::<1>(&mut reader)?[0] != 0 {
+            let (x, y, size) = (
+                read_scalar(&mut reader)?,
+                read_scalar(&mut reader)?,
+                read_scalar(&mut reader)?,
+            );
+            Some(Square::new(x, y, size))
+        } else {
+            None
+        };
+        let node_count = read_u32(&mut reader)?;
+        let value_count = read_u32(&mut reader)?;
+        let mut nodes = Vec::with_capacity(node_count.min(MAX_RESERVE) as usize);
+        for _ in 0..node_count {
+            let (x, y, size) = (
+                read_scalar(&mut reader)?,
+                read_scalar(&mut reader)?,
+                read_scalar(&mut reader)?,
+            );
+            nodes.push(FlatNode {
+                region: Square::new(x, y, size),
+                next: read_u32(&mut reader)?,
+                leaf: read_array::<1>(&mut reader)?[0] != 0,
+                start: read_u32(&mut reader)?,
+                end: read_u32(&mut reader)?,
+            });
+        }
+        check_nodes(&nodes, value_count).map_err(invalid)?;
+
+        let mut values = Vec::with_capacity(value_count.min(MAX_RESERVE) as usize);
+        let mut blob = Vec::new();
+        for _ in 0..value_count {
+            let len = read_u32(&mut reader)? as usize;
+            blob.clear();
+            if (&mut reader).take(len as u64).read_to_end(&mut blob)? != len {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            values.push(bincode::deserialize(&blob).map_err(invalid)?);
+        }
+
+        let checksum = reader.value();
+        if read_u32(&mut reader.inner)? != checksum {
+            return Err(invalid("quadtree binary checksum mismatch"));
+        }
+
+        Ok(QTree {
+            config,
+            base,
+            nodes,
+            values,
+        })
+    }
+}
+
+impl<T: Clone + Positioned> QTreeMut<T> {
+    pub fn write_binary<W: Write>(&self, writer: W) -> io::Result<()>
+    where
+        T: Serialize,
+    {
+        QTree::from(self.clone()).write_binary(writer)
+    }
+
+    pub fn read_binary<R: Read>(reader: R) -> io::Result<Self>
+    where
+        T: DeserializeOwned,
+    {
+        QTree::read_binary(reader).map(Self::from)
+    }
+}
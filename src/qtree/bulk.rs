@@ -0,0 +1,152 @@
+use super::helpers::*;
+use super::*;
+
+#[cfg(feature = "rayon")]
+const PAR_THRESHOLD: usize = 4096;
+
+fn bounding_square<T: Positioned>(
+    values: &[T],
+    config: &QTreeConfig<T::Scalar>,
+) -> Option<Square<T::Scalar>> {
+    if !values.iter().all(|v| v.bounds().is_finite()) {
+        return None;
+    }
+    let mut bounds = values.iter().map(Positioned::bounds);
+    let first = bounds.next().unwrap_or_default();
+    let (x, y, right, bottom) = bounds.fold(
+        (first.x, first.y, first.right(), first.bottom()),
+        |(x, y, right, bottom), b| {
+            let (x, y) = (x.at_most(b.x), y.at_most(b.y));
+            (x, y, right.at_least(b.right()), bottom.at_least(b.bottom()))
+        },
+    );
+    let rect = Aabb::new(x, y, right.checked_sub(x)?, bottom.checked_sub(y)?);
+    let extent = rect
+        .w
+        .at_least(rect.h)
+        .at_least(config.min_size)
+        .at_least(T::Scalar::ONE);
+    let mut size = T::Scalar::square_size(extent)?;
+    // aligning to half the size keeps quadrant edges exact far from the origin
+    loop {
+        let half = (size / T::Scalar::TWO).at_least(T::Scalar::ONE);
+        if let Some(square) = Square::checked(rect.x.snap(half), rect.y.snap(half), size)
+            && square.encloses(&rect)
+        {
+            return Some(square);
+        }
+        size = size.checked_mul(T::Scalar::TWO)?;
+    }
+}
+
+fn partition<T: Positioned>(region: &Square<T::Scalar>, values: Vec<T>) -> (Vec<T>, [Vec<T>; 4]) {
+    let quadrants = region.quadrants();
+    let mut own = Vec::new();
+    let mut buckets: [Vec<T>; 4] = Default::default();
+    for value in values {
+        let bounds = value.bounds();
+        let i = Quadrant::new(region, bounds.point()) as usize;
+        if quadrants[i].encloses(&bounds) {
+            buckets[i].push(value);
+        } else {
+            own.push(value);
+        }
+    }
+    (own, buckets)
+}
+
+impl<T: Clone + Positioned> QNode<T> {
+    fn build(
+        region: Square<T::Scalar>,
+        values: Vec<T>,
+        config: &QTreeConfig<T::Scalar>,
+        depth: usize,
+    ) -> Self {
+        if values.len() <= config.capacity || !config.can_split(&region, depth) {
+            return Self::new(region, values);
+        }
+        let (own, buckets) = partition(&region, values);
+        let quadrants = region.quadrants().into_iter().zip(buckets);
+        let children = quadrants.map(|(rect, bucket)| Self::build(rect, bucket, config, depth + 1));
+        Self::Node {
+            region,
+            children: children.collect(),
+            values: own,
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_build(
+        region: Square<T::Scalar>,
+        values: Vec<T>,
+        config: &QTreeConfig<T::Scalar>,
+        depth: usize,
+    ) -> Self
+    where
+        T: Send,
+    {
+        use rayon::prelude::*;
+
+        if values.len() < PAR_THRESHOLD || !config.can_split(&region, depth) {
+            return Self::build(region, values, config, depth);
+        }
+        let (own, buckets) = partition(&region, values);
+        let quadrants = region.quadrants().into_par_iter().zip(buckets);
+        let children =
+            quadrants.map(|(rect, bucket)| Self::par_build(rect, bucket, config, depth + 1));
+        Self::Node {
+            region,
+            children: children.collect(),
+            values: own,
+        }
+    }
+}
+
+impl<T: Clone + Positioned> QTreeMut<T> {
+    pub fn from_values<I: IntoIterator<Item = T>>(values: I) -> Self {
+        Self::from_values_with(values, QTreeConfig::default())
+    }
+
+    pub fn from_values_with<I: IntoIterator<Item = T>>(
+        values: I,
+        config: QTreeConfig<T::Scalar>,
+    ) -> Self {
+        Self::try_from_values_with(values, config).expect("quadtree region out of range")
+    }
+
+    pub fn try_from_values_with<I: IntoIterator<Item = T>>(
+        values: I,
+        config: QTreeConfig<T::Scalar>,
+    ) -> Result<Self, OutOfRange> {
+        let values: Vec<T> = values.into_iter().collect();
+        let region = bounding_square(&values, &config).ok_or(OutOfRange)?;
+        Ok(QTreeMut {
+            config,
+            root: QNode::build(region, values, &config, 0),
+            base: Some(region),
+        })
+    }
+
+    #[cfg(feature = "rayon")]
+    pub fn par_from_values<I: IntoIterator<Item = T>>(
+        values: I,
+        config: QTreeConfig<T::Scalar>,
+    ) -> Self
+    where
+        T: Send,
+    {
+        let values: Vec<T> = values.into_iter().collect();
+        let region = bounding_square(&values, &config).expect("quadtree region out of range");
+        QTreeMut {
+            config,
+            root: QNode::par_build(region, values, &config, 0),
+            base: Some(region),
+        }
+    }
+}
+
+impl<T: Clone + Positioned> FromIterator<T> for QTreeMut<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_values(iter)
+    }
+}
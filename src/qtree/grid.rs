@@ -0,0 +1,142 @@
+use super::*;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+pub struct HashGrid<T: Positioned> {
+    cell: T::Scalar,
+    cells: HashMap<IVec2, Vec<T>>,
+    // widest reach of any bounds from its position, so queries can look past cell edges
+    margin: f64,
+    len: usize,
+}
+
+impl<T: Positioned> HashGrid<T> {
+    pub fn new(cell: T::Scalar) -> Self {
+        HashGrid {
+            cell,
+            cells: HashMap::new(),
+            margin: 0.0,
+            len: 0,
+        }
+    }
+
+    pub fn cell(&self) -> T::Scalar {
+        self.cell
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.values().flatten()
+    }
+
+    fn key(&self, x: f64, y: f64) -> IVec2 {
+        cell_key(self.cell.to_f64(), x, y)
+    }
+}
+
+pub(super) fn cell_key(cell: f64, x: f64, y: f64) -> IVec2 {
+    ivec2((x / cell).round() as i32, (y / cell).round() as i32)
+}
+
+pub(super) fn closest<'a, T: Positioned>(
+    values: impl Iterator<Item = &'a T>,
+    pos: Vector<T::Scalar>,
+) -> Option<(f64, &'a T)> {
+    let values = values.map(|v| (distance_squared::<T::Scalar>(v.pos(), pos), v));
+    values.min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+pub(super) fn reach<T: Positioned>(value: &T) -> f64 {
+    let (bounds, (x, y)) = (value.bounds(), T::Scalar::xy(value.pos()));
+    let (x, y) = (x.to_f64(), y.to_f64());
+    [
+        x - bounds.x.to_f64(),
+        bounds.right().to_f64() - x,
+        y - bounds.y.to_f64(),
+        bounds.bottom().to_f64() - y,
+    ]
+    .into_iter()
+    .fold(0.0, f64::max)
+}
+
+impl<T: Positioned> SpatialIndex<T> for HashGrid<T> {
+    fn insert(&mut self, value: T) {
+        self.margin = self.margin.max(reach(&value));
+        let key = value.coords(self.cell);
+        self.cells.entry(key).or_default().push(value);
+        self.len += 1;
+    }
+
+    fn remove(&mut self, pos: Vector<T::Scalar>) -> Vec<T> {
+        let (x, y) = T::Scalar::xy(pos);
+        let key = self.key(x.to_f64(), y.to_f64());
+        let Some(values) = self.cells.get_mut(&key) else {
+            return Vec::new();
+        };
+        let removed: Vec<T> = values.extract_if(.., |v| v.pos() == pos).collect();
+        if values.is_empty() {
+            self.cells.remove(&key);
+        }
+        self.len -= removed.len();
+        removed
+    }
+
+    fn query<'a>(&'a self, area: Aabb<T::Scalar>) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        let margin = self.margin;
+        let lo = self.key(area.x.to_f64() - margin, area.y.to_f64() - margin);
+        let hi = self.key(
+            area.right().to_f64() + margin,
+            area.bottom().to_f64() + margin,
+        );
+        let span = (hi.as_i64vec2() - lo.as_i64vec2()).max(I64Vec2::NEG_ONE) + 1;
+        // past as many keys as there are occupied cells, walking the cells is cheaper
+        let cells: Vec<&Vec<T>> = if span.x.saturating_mul(span.y) > self.cells.len() as i64 {
+            let inside = |key: &IVec2| key.cmpge(lo).all() && key.cmple(hi).all();
+            let cells = self.cells.iter().filter(|(key, _)| inside(key));
+            cells.map(|(_, values)| values).collect()
+        } else {
+            let keys = (lo.y..=hi.y).flat_map(|y| (lo.x..=hi.x).map(move |x| ivec2(x, y)));
+            keys.filter_map(|key| self.cells.get(&key)).collect()
+        };
+        let values = cells.into_iter().flatten();
+        values.filter(move |value| area.overlaps(&value.bounds()))
+    }
+
+    fn nearest(&self, pos: Vector<T::Scalar>) -> Option<&T> {
+        let (x, y) = T::Scalar::xy(pos);
+        let center = self.key(x.to_f64(), y.to_f64());
+        let cell = self.cell.to_f64();
+
+        let mut best: Option<(f64, &T)> = None;
+        for r in 0i32.. {
+            // once a ring outgrows the occupied cells a full scan is cheaper
+            if 8 * r as usize >= self.cells.len() {
+                return closest(self.iter(), pos).map(|(_, v)| v);
+            }
+            let ring = (-r..=r).flat_map(|dy| (-r..=r).map(move |dx| ivec2(dx, dy)));
+            let ring = ring.filter(|d| d.x.abs().max(d.y.abs()) == r);
+            let values = ring.filter_map(|d| self.cells.get(&(center + d))).flatten();
+            if let Some(found) = closest(values, pos)
+                && best.is_none_or(|(d, _)| found.0 < d)
+            {
+                best = Some(found);
+            }
+            if let Some((d, value)) = best
+                && d <= (r as f64 * cell).powi(2)
+            {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::cmp::Ordering;
 
 #[derive(Copy, Clone, PartialEq)]
 pub enum Quadrant {
@@ -9,16 +10,17 @@ pub enum Quadrant {
 }
 
 impl Quadrant {
-    pub fn new(region: &Rect, pos: Vec2) -> Self {
-        let center = region.center();
-        if pos.x < center.x {
-            if pos.y < center.y {
+    pub fn new<S: Scalar>(region: &Aabb<S>, pos: Vector<S>) -> Self {
+        let (x, y) = S::xy(pos);
+        let (cx, cy) = S::xy(region.center());
+        if x < cx {
+            if y < cy {
                 Quadrant::TopLeft
             } else {
                 Quadrant::BottomLeft
             }
         } else {
-            if pos.y < center.y {
+            if y < cy {
                 Quadrant::TopRight
             } else {
                 Quadrant::BottomRight
@@ -27,52 +29,114 @@ impl Quadrant {
     }
 }
 
-#[derive(Serialize, Deserialize)]
-#[serde(remote = "Rect")]
-struct RectDef {
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
+pub enum Entry<N, V> {
+    Node(N),
+    Value(V),
+}
+
+pub struct Candidate<E> {
+    pub dist: f64,
+    pub entry: E,
+}
+
+impl<E> PartialEq for Candidate<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl<E> Eq for Candidate<E> {}
+
+impl<E> PartialOrd for Candidate<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<E> Ord for Candidate<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.dist.total_cmp(&self.dist)
+    }
 }
 
 #[repr(transparent)]
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
-pub struct Square(#[serde(with = "RectDef")] Rect);
+#[serde(bound = "")]
+pub struct Square<S: Scalar = f32>(Aabb<S>);
+
+impl<S: Scalar> Square<S> {
+    pub fn new(x: S, y: S, size: S) -> Self {
+        Square(Aabb::new(x, y, size, size))
+    }
+
+    pub fn modify(&self, dx: S, dy: S, size: S) -> Self {
+        Self::new(self.x + dx, self.y + dy, size)
+    }
+
+    pub fn checked(x: S, y: S, size: S) -> Option<Self> {
+        x.checked_add(size)?;
+        y.checked_add(size)?;
+        Some(Self::new(x, y, size))
+    }
+
+    pub fn checked_modify(&self, dx: S, dy: S, size: S) -> Option<Self> {
+        Self::checked(self.x.checked_add(dx)?, self.y.checked_add(dy)?, size)
+    }
+
+    pub fn zero(pos: Vector<S>) -> Self {
+        Square(Aabb::at(pos))
+    }
+
+    pub fn quadrants(&self) -> [Self; 4] {
+        let size = self.w / S::TWO;
+        let zero = S::ZERO;
+        [
+            self.modify(zero, zero, size),
+            self.modify(size, zero, size),
+            self.modify(zero, size, size),
+            self.modify(size, size, size),
+        ]
+    }
 
-impl Square {
-    pub fn new(x: f32, y: f32, size: f32) -> Self {
-        Square(Rect {
-            x,
-            y,
-            w: size,
-            h: size,
-        })
+    pub fn encloses(&self, rect: &Aabb<S>) -> bool {
+        self.x <= rect.x
+            && self.y <= rect.y
+            && rect.right() <= self.right()
+            && rect.bottom() <= self.bottom()
     }
 
-    pub fn modify(&self, diff: Vec2, size: f32) -> Self {
-        Self::new(self.x + diff.x, self.y + diff.y, size)
+    pub fn holds(&self, bounds: &Aabb<S>, leaf: bool) -> bool {
+        let quadrant = self.quadrants()[Quadrant::new(self, bounds.point()) as usize];
+        let routed = self.contains(bounds.point()) && self.encloses(bounds);
+        routed && (leaf || !quadrant.encloses(bounds))
     }
 
-    pub fn zero(pos: Vec2) -> Self {
-        Self::new(pos.x, pos.y, 0.0)
+    pub fn distance_squared(&self, pos: Vector<S>) -> f64 {
+        let (x, y) = S::xy(pos);
+        let outside = |p: S, min: S, max: S| {
+            let (p, min, max) = (p.to_f64(), min.to_f64(), max.to_f64());
+            (min - p).max(p - max).max(0.0)
+        };
+        let (dx, dy) = (
+            outside(x, self.x, self.right()),
+            outside(y, self.y, self.bottom()),
+        );
+        dx * dx + dy * dy
     }
 }
 
-impl Deref for Square {
-    type Target = Rect;
+impl<S: Scalar> Deref for Square<S> {
+    type Target = Aabb<S>;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
-impl DerefMut for Square {
+impl<S: Scalar> DerefMut for Square<S> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
 }
 
-impl From<Rect> for Square {
-    fn from(rect: Rect) -> Self {
+impl<S: Scalar> From<Aabb<S>> for Square<S> {
+    fn from(rect: Aabb<S>) -> Self {
         Square(rect)
     }
 }
@@ -0,0 +1,108 @@
+use super::*;
+
+pub trait Visitor<T: Clone + Positioned> {
+    fn enter(&mut self, _node: &QNode<T>, _depth: usize) -> bool {
+        true
+    }
+
+    fn leave(&mut self, _node: &QNode<T>, _depth: usize) {}
+}
+
+pub struct Nodes<'a, T: Clone + Positioned, F: Fn(&Square<T::Scalar>) -> bool> {
+    stack: Vec<&'a QNode<T>>,
+    visit: F,
+}
+
+impl<'a, T: Clone + Positioned, F: Fn(&Square<T::Scalar>) -> bool> Iterator for Nodes<'a, T, F> {
+    type Item = &'a QNode<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        let visit = &self.visit;
+        let children = node.children().iter().rev();
+        self.stack
+            .extend(children.filter(|child| visit(&child.region())));
+        Some(node)
+    }
+}
+
+pub struct IterMut<'a, T: Clone + Positioned> {
+    stack: Vec<&'a mut QNode<T>>,
+    values: std::slice::IterMut<'a, T>,
+}
+
+impl<'a, T: Clone + Positioned> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.values.next() {
+                return Some(value);
+            }
+            match self.stack.pop()? {
+                QNode::Node {
+                    children, values, ..
+                } => {
+                    self.values = values.iter_mut();
+                    self.stack.extend(children.iter_mut().rev());
+                }
+                QNode::Leaf { values, .. } => self.values = values.iter_mut(),
+            }
+        }
+    }
+}
+
+impl<T: Clone + Positioned> QTreeMut<T> {
+    pub fn nodes_where<F: Fn(&Square<T::Scalar>) -> bool>(&self, visit: F) -> Nodes<'_, T, F> {
+        let stack = if visit(&self.region()) {
+            vec![&self.root]
+        } else {
+            Vec::new()
+        };
+        Nodes { stack, visit }
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &QNode<T>> {
+        self.nodes_where(|_| true)
+    }
+
+    pub fn leaves(&self) -> impl Iterator<Item = (Square<T::Scalar>, &[T])> {
+        let leaves = self.nodes().filter(|node| node.is_leaf());
+        leaves.map(|node| (node.region(), node.values()))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.nodes().flat_map(QNode::values)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            stack: vec![&mut self.root],
+            values: [].iter_mut(),
+        }
+    }
+
+    pub fn visit<V: Visitor<T>>(&self, visitor: &mut V) {
+        self.root.visit0(visitor, 0);
+    }
+}
+
+impl<T: Clone + Positioned> QNode<T> {
+    fn visit0<V: Visitor<T>>(&self, visitor: &mut V, depth: usize) {
+        if visitor.enter(self, depth) {
+            for node in self.children() {
+                node.visit0(visitor, depth + 1);
+            }
+        }
+        visitor.leave(self, depth);
+    }
+}
+
+impl<T: Clone + Positioned> IntoIterator for QTreeMut<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.root.into_values().into_iter()
+    }
+}
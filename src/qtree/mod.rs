@@ -1,6 +1,34 @@
+mod binary;
+mod bulk;
+mod grid;
 mod helpers;
+mod iter;
+mod nearest;
+#[cfg(feature = "rayon")]
+mod par;
 mod prelude;
+mod qtree_frozen;
 mod qtree_impl;
+mod query;
+mod raycast;
+#[cfg(feature = "render")]
+mod render;
+mod scalar;
+mod spatial;
+mod stats;
+mod zorder;
 
+pub use binary::*;
+pub use grid::*;
+pub use helpers::Square;
+pub use iter::*;
 pub use prelude::*;
+pub use qtree_frozen::*;
 pub use qtree_impl::*;
+pub use raycast::*;
+#[cfg(feature = "render")]
+pub use render::*;
+pub use scalar::*;
+pub use spatial::*;
+pub use stats::*;
+pub use zorder::*;
@@ -0,0 +1,54 @@
+use super::helpers::*;
+use super::*;
+use std::collections::BinaryHeap;
+
+struct Nearest<'a, T: Clone + Positioned> {
+    pos: Vector<T::Scalar>,
+    heap: BinaryHeap<Candidate<Entry<&'a QNode<T>, &'a T>>>,
+}
+
+impl<'a, T: Clone + Positioned> Iterator for Nearest<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.heap.pop()?.entry {
+                Entry::Value(value) => return Some(value),
+                Entry::Node(node) => {
+                    let pos = self.pos;
+                    let children = node.children().iter();
+                    self.heap.extend(children.map(|child| Candidate {
+                        dist: child.region().distance_squared(pos),
+                        entry: Entry::Node(child),
+                    }));
+                    let values = node.values().iter();
+                    self.heap.extend(values.map(|value| Candidate {
+                        dist: distance_squared::<T::Scalar>(value.pos(), pos),
+                        entry: Entry::Value(value),
+                    }));
+                }
+            }
+        }
+    }
+}
+
+impl<T: Clone + Positioned> QTreeMut<T> {
+    pub fn nearest_iter(&self, pos: Vector<T::Scalar>) -> impl Iterator<Item = &T> {
+        let root = Candidate {
+            dist: self.region().distance_squared(pos),
+            entry: Entry::Node(self.root()),
+        };
+        Nearest {
+            pos,
+            heap: BinaryHeap::from([root]),
+        }
+    }
+
+    pub fn nearest(&self, pos: Vector<T::Scalar>) -> Option<&T> {
+        self.nearest_iter(pos).next()
+    }
+
+    pub fn k_nearest(&self, pos: Vector<T::Scalar>, k: usize) -> Vec<&T> {
+        self.nearest_iter(pos).take(k).collect()
+    }
+}
@@ -0,0 +1,30 @@
+use super::*;
+use rayon::prelude::*;
+
+macro_rules! par_queries {
+    ($($tree:ident),*) => {$(
+        impl<T: Clone + Positioned + Sync> $tree<T> {
+            pub fn par_query_many<A>(&self, areas: &[A]) -> Vec<Vec<&T>>
+            where
+                A: Copy + Into<Aabb<T::Scalar>> + Sync,
+            {
+                let areas = areas.par_iter();
+                areas.map(|&area| self.query(area).collect()).collect()
+            }
+
+            pub fn par_nearest_many(&self, points: &[Vector<T::Scalar>]) -> Vec<Option<&T>> {
+                points.par_iter().map(|&pos| self.nearest(pos)).collect()
+            }
+
+            pub fn par_k_nearest_many(
+                &self,
+                points: &[Vector<T::Scalar>],
+                k: usize,
+            ) -> Vec<Vec<&T>> {
+                points.par_iter().map(|&pos| self.k_nearest(pos, k)).collect()
+            }
+        }
+    )*};
+}
+
+par_queries!(QTreeMut, QTree, ZOrderTree);
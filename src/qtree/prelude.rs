@@ -1,3 +1,3 @@
-pub use macroquad::math::*;
+pub use glam::*;
 pub use serde::{Deserialize, Serialize};
 pub use std::ops::{Deref, DerefMut};
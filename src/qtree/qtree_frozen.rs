@@ -0,0 +1,285 @@
+use super::helpers::*;
+use super::*;
+use std::collections::BinaryHeap;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(bound = "")]
+pub struct FlatNode<S: Scalar = f32> {
+    pub region: Square<S>,
+    pub next: u32,
+    pub leaf: bool,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(try_from = "RawQTree<T>")]
+pub struct QTree<T: Clone + Positioned> {
+    pub(super) config: QTreeConfig<T::Scalar>,
+    pub(super) base: Option<Square<T::Scalar>>,
+    pub(super) nodes: Vec<FlatNode<T::Scalar>>,
+    pub(super) values: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct RawQTree<T: Clone + Positioned> {
+    config: QTreeConfig<T::Scalar>,
+    #[serde(default)]
+    base: Option<Square<T::Scalar>>,
+    nodes: Vec<FlatNode<T::Scalar>>,
+    values: Vec<T>,
+}
+
+impl<T: Clone + Positioned> TryFrom<RawQTree<T>> for QTree<T> {
+    type Error = String;
+
+    fn try_from(raw: RawQTree<T>) -> Result<Self, String> {
+        let value_count = u32::try_from(raw.values.len()).map_err(|e| e.to_string())?;
+        check_nodes(&raw.nodes, value_count)?;
+        Ok(QTree {
+            config: raw.config,
+            base: raw.base,
+            nodes: raw.nodes,
+            values: raw.values,
+        })
+    }
+}
+
+// queries index the node table blindly, so loaded tables are checked up front
+pub(super) fn check_nodes<S: Scalar>(
+    nodes: &[FlatNode<S>],
+    value_count: u32,
+) -> Result<(), String> {
+    let node_count = u32::try_from(nodes.len()).map_err(|e| e.to_string())?;
+    let Some(root) = nodes.first() else {
+        return Err("quadtree without a root node".into());
+    };
+    for (i, node) in (0..).zip(nodes) {
+        let children = node.leaf || i + 1 < node.next;
+        if node.next <= i || node.next > node_count || !children {
+            return Err(format!("broken node link at {i}"));
+        }
+        if node.start > node.end || node.end > value_count {
+            return Err(format!("broken value range at {i}"));
+        }
+    }
+    if root.next != node_count {
+        return Err("root does not span the node table".into());
+    }
+    for (i, node) in nodes.iter().enumerate().filter(|(_, node)| !node.leaf) {
+        // the four child subtrees must chain up to exactly the end of this one
+        let mut child = i as u32 + 1;
+        for _ in 0..4 {
+            if child >= node.next {
+                return Err(format!("missing children at {i}"));
+            }
+            child = nodes[child as usize].next;
+        }
+        if child != node.next {
+            return Err(format!("broken node link at {i}"));
+        }
+    }
+    Ok(())
+}
+
+impl<T: Clone + Positioned> QTree<T> {
+    pub fn config(&self) -> &QTreeConfig<T::Scalar> {
+        &self.config
+    }
+
+    pub fn region(&self) -> Square<T::Scalar> {
+        self.nodes[0].region
+    }
+
+    pub fn size(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn nodes(&self) -> &[FlatNode<T::Scalar>] {
+        &self.nodes
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    fn node_values(&self, node: &FlatNode<T::Scalar>) -> &[T] {
+        &self.values[node.start as usize..node.end as usize]
+    }
+
+    fn children(&self, i: usize) -> impl Iterator<Item = usize> {
+        let count = if self.nodes[i].leaf { 0 } else { 4 };
+        let mut child = i + 1;
+        (0..count).map(move |_| {
+            let current = child;
+            child = self.nodes[current].next as usize;
+            current
+        })
+    }
+
+    fn flatten(&mut self, tree: QNode<T>) {
+        let i = self.nodes.len();
+        let start = self.values.len() as u32;
+        let (region, leaf, children) = match tree {
+            QNode::Node {
+                region,
+                children,
+                values,
+            } => {
+                self.values.extend(values);
+                (region, false, children)
+            }
+            QNode::Leaf { region, values } => {
+                self.values.extend(values);
+                (region, true, Vec::new())
+            }
+        };
+        self.nodes.push(FlatNode {
+            region,
+            next: 0,
+            leaf,
+            start,
+            end: self.values.len() as u32,
+        });
+        for node in children {
+            self.flatten(node);
+        }
+        self.nodes[i].next = self.nodes.len() as u32;
+    }
+
+    fn rebuild(&self, i: usize, values: &mut impl Iterator<Item = T>) -> QNode<T> {
+        let node = self.nodes[i];
+        let count = (node.end - node.start) as usize;
+        let own = values.by_ref().take(count).collect();
+        if node.leaf {
+            QNode::new(node.region, own)
+        } else {
+            let children = self.children(i).map(|c| self.rebuild(c, values)).collect();
+            QNode::Node {
+                region: node.region,
+                children,
+                values: own,
+            }
+        }
+    }
+
+    pub fn query(&self, area: impl Into<Aabb<T::Scalar>>) -> impl Iterator<Item = &T> {
+        Query {
+            tree: self,
+            area: area.into(),
+            node: 0,
+            values: [].iter(),
+        }
+    }
+
+    pub fn nearest_iter(&self, pos: Vector<T::Scalar>) -> impl Iterator<Item = &T> {
+        let root = Candidate {
+            dist: self.region().distance_squared(pos),
+            entry: Entry::Node(0),
+        };
+        Nearest {
+            tree: self,
+            pos,
+            heap: BinaryHeap::from([root]),
+        }
+    }
+
+    pub fn nearest(&self, pos: Vector<T::Scalar>) -> Option<&T> {
+        self.nearest_iter(pos).next()
+    }
+
+    pub fn k_nearest(&self, pos: Vector<T::Scalar>, k: usize) -> Vec<&T> {
+        self.nearest_iter(pos).take(k).collect()
+    }
+}
+
+impl<T: Clone + Positioned> From<QTreeMut<T>> for QTree<T> {
+    fn from(tree: QTreeMut<T>) -> Self {
+        let mut flat = QTree {
+            config: tree.config,
+            base: tree.base,
+            nodes: Vec::with_capacity(tree.size()),
+            values: Vec::new(),
+        };
+        flat.flatten(tree.root);
+        flat
+    }
+}
+
+impl<T: Clone + Positioned> From<QTree<T>> for QTreeMut<T> {
+    fn from(mut tree: QTree<T>) -> Self {
+        let values = std::mem::take(&mut tree.values);
+        let root = tree.rebuild(0, &mut values.into_iter());
+        QTreeMut {
+            config: tree.config,
+            base: tree.base,
+            root,
+        }
+    }
+}
+
+struct Query<'a, T: Clone + Positioned> {
+    tree: &'a QTree<T>,
+    area: Aabb<T::Scalar>,
+    node: usize,
+    values: std::slice::Iter<'a, T>,
+}
+
+impl<'a, T: Clone + Positioned> Iterator for Query<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            for value in self.values.by_ref() {
+                if self.area.overlaps(&value.bounds()) {
+                    return Some(value);
+                }
+            }
+            let node = self.tree.nodes.get(self.node)?;
+            if node.region.overlaps(&self.area) {
+                self.values = self.tree.node_values(node).iter();
+                self.node += 1;
+            } else {
+                self.node = node.next as usize;
+            }
+        }
+    }
+}
+
+struct Nearest<'a, T: Clone + Positioned> {
+    tree: &'a QTree<T>,
+    pos: Vector<T::Scalar>,
+    heap: BinaryHeap<Candidate<Entry<usize, &'a T>>>,
+}
+
+impl<'a, T: Clone + Positioned> Iterator for Nearest<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.heap.pop()?.entry {
+                Entry::Value(value) => return Some(value),
+                Entry::Node(i) => {
+                    let (tree, pos) = (self.tree, self.pos);
+                    self.heap.extend(tree.children(i).map(|c| Candidate {
+                        dist: tree.nodes[c].region.distance_squared(pos),
+                        entry: Entry::Node(c),
+                    }));
+                    let values = tree.node_values(&tree.nodes[i]).iter();
+                    self.heap.extend(values.map(|value| Candidate {
+                        dist: distance_squared::<T::Scalar>(value.pos(), pos),
+                        entry: Entry::Value(value),
+                    }));
+                }
+            }
+        }
+    }
+}
@@ -1,125 +1,521 @@
 use super::helpers::*;
 use super::*;
+use std::fmt;
 
 pub trait Positioned {
-    fn pos(&self) -> Vec2;
+    type Scalar: Scalar;
 
-    fn coords(&self, cell: f32) -> IVec2 {
-        let pos = self.pos();
-        ivec2((pos.x / cell).round() as i32, (pos.y / cell).round() as i32)
+    fn pos(&self) -> Vector<Self::Scalar>;
+
+    fn coords(&self, cell: Self::Scalar) -> IVec2 {
+        let (x, y) = Self::Scalar::xy(self.pos());
+        let cell = cell.to_f64();
+        ivec2(
+            (x.to_f64() / cell).round() as i32,
+            (y.to_f64() / cell).round() as i32,
+        )
     }
 
-    fn draw(&self) {}
+    fn bounds(&self) -> Aabb<Self::Scalar> {
+        Aabb::at(self.pos())
+    }
 }
 
-impl<T: Copy + Into<Vec2>> Positioned for T {
+impl Positioned for Vec2 {
+    type Scalar = f32;
+
     fn pos(&self) -> Vec2 {
-        (*self).into()
+        *self
+    }
+}
+
+impl Positioned for DVec2 {
+    type Scalar = f64;
+
+    fn pos(&self) -> DVec2 {
+        *self
+    }
+}
+
+impl Positioned for IVec2 {
+    type Scalar = i32;
+
+    fn pos(&self) -> IVec2 {
+        *self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfRange;
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value lies outside any region the scalar type can hold")
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(bound = "")]
+pub struct QTreeConfig<S: Scalar = f32> {
+    pub capacity: usize,
+    pub min_size: S,
+    pub max_depth: usize,
+}
+
+impl<S: Scalar> Default for QTreeConfig<S> {
+    fn default() -> Self {
+        QTreeConfig {
+            capacity: 4,
+            min_size: S::ONE,
+            max_depth: 16,
+        }
+    }
+}
+
+impl<S: Scalar> QTreeConfig<S> {
+    pub(super) fn can_split(&self, region: &Square<S>, depth: usize) -> bool {
+        region.w / S::TWO >= self.min_size && depth < self.max_depth
     }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
-pub enum QTreeMut<T: Clone + Positioned> {
+pub enum QNode<T: Clone + Positioned> {
     Node {
-        region: Square,
-        children: Vec<QTreeMut<T>>,
+        region: Square<T::Scalar>,
+        children: Vec<QNode<T>>,
+        values: Vec<T>,
     },
     Leaf {
-        region: Square,
+        region: Square<T::Scalar>,
         values: Vec<T>,
     },
 }
 
-impl<T: Clone + Positioned> QTreeMut<T> {
-    pub fn new(region: Square, values: Vec<T>) -> Self {
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QTreeMut<T: Clone + Positioned> {
+    pub(super) config: QTreeConfig<T::Scalar>,
+    pub(super) root: QNode<T>,
+    // removals never shrink the root below the region the tree was built with;
+    // trees saved before this was recorded have none
+    #[serde(default)]
+    pub(super) base: Option<Square<T::Scalar>>,
+}
+
+impl<T: Clone + Positioned> QNode<T> {
+    pub fn new(region: Square<T::Scalar>, values: Vec<T>) -> Self {
         Self::Leaf { region, values }
     }
 
-    // pub fn from(tree: QTree<T>) -> Self {}
-
-    pub fn region(&self) -> Square {
+    pub fn region(&self) -> Square<T::Scalar> {
         match self {
             Self::Node { region, .. } => *region,
             Self::Leaf { region, .. } => *region,
         }
     }
 
-    fn cell_size(&self) -> f32 {
+    pub fn values(&self) -> &[T] {
+        match self {
+            Self::Node { values, .. } => values,
+            Self::Leaf { values, .. } => values,
+        }
+    }
+
+    pub fn children(&self) -> &[Self] {
         match self {
-            Self::Node { children, .. } => {
-                let arr = children.iter().map(|node| node.cell_size());
-                arr.reduce(f32::min).unwrap_or(self.region().w)
+            Self::Node { children, .. } => children,
+            Self::Leaf { .. } => &[],
+        }
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        matches!(self, Self::Leaf { .. })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Node {
+                children, values, ..
+            } => values.is_empty() && children.iter().all(Self::is_empty),
+            Self::Leaf { values, .. } => values.is_empty(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Node { children, .. } => children.iter().map(Self::size).sum::<usize>() + 1,
+            Self::Leaf { .. } => 1,
+        }
+    }
+
+    pub(super) fn into_values(self) -> Vec<T> {
+        match self {
+            Self::Node {
+                children,
+                mut values,
+                ..
+            } => {
+                values.extend(children.into_iter().flat_map(Self::into_values));
+                values
             }
-            Self::Leaf { .. } => self.region().w,
+            Self::Leaf { values, .. } => values,
         }
     }
 
-    fn blank_children(region: Square) -> Vec<Self> {
-        let size = region.w / 2.0;
-        [
-            region.modify(Vec2::ZERO, size),
-            region.modify(vec2(size, 0.0), size),
-            region.modify(vec2(0.0, size), size),
-            region.modify(vec2(size, size), size),
-        ]
-        .map(|rect| Self::new(rect, Vec::new()))
-        .to_vec()
+    fn blank_children(region: Square<T::Scalar>) -> Vec<Self> {
+        let quadrants = region.quadrants();
+        quadrants.map(|rect| Self::new(rect, Vec::new())).to_vec()
     }
 
-    fn expand_to_contain(&mut self, pos: Vec2) {
+    fn expand_to_contain(
+        &mut self,
+        bounds: Aabb<T::Scalar>,
+        config: &QTreeConfig<T::Scalar>,
+    ) -> Option<()> {
         let region = self.region();
-        let treat_as = Quadrant::new(&Square::zero(pos), region.center());
+        let treat_as = match (bounds.x < region.x, bounds.y < region.y) {
+            (false, false) => Quadrant::TopLeft,
+            (true, false) => Quadrant::TopRight,
+            (false, true) => Quadrant::BottomLeft,
+            (true, true) => Quadrant::BottomRight,
+        };
 
+        let (zero, size) = (T::Scalar::ZERO, region.w.checked_mul(T::Scalar::TWO)?);
         let rect = match treat_as {
-            Quadrant::TopLeft => region.modify(Vec2::ZERO, region.w * 2.0),
-            Quadrant::TopRight => region.modify(vec2(-region.w, 0.0), region.w * 2.0),
-            Quadrant::BottomLeft => region.modify(vec2(0.0, -region.h), region.w * 2.0),
-            Quadrant::BottomRight => region.modify(vec2(-region.w, -region.h), region.w * 2.0),
-        };
+            Quadrant::TopLeft => region.checked_modify(zero, zero, size),
+            Quadrant::TopRight => region.checked_modify(zero - region.w, zero, size),
+            Quadrant::BottomLeft => region.checked_modify(zero, zero - region.h, size),
+            Quadrant::BottomRight => region.checked_modify(zero - region.w, zero - region.h, size),
+        }?;
+        // far from the origin the grown corner can round off, which would leave
+        // the old root outside its own quadrant
+        if rect.quadrants()[treat_as as usize] != region {
+            return None;
+        }
 
-        let mut children = Self::blank_children(rect);
+        // values on the far edges were only held by the closed root bounds
+        let mut stranded = Vec::new();
+        let mut edge = |v: &T| !region.contains(v.bounds().point());
+        self.remove_where0(&mut edge, config, &mut stranded);
 
+        let mut children = Self::blank_children(rect);
         children[treat_as as usize] = self.clone();
         *self = Self::Node {
             region: rect,
             children,
+            values: Vec::new(),
         };
+        for value in stranded {
+            self.add0(value, config, 0);
+        }
+        Some(())
     }
 
-    fn add0(&mut self, value: T, target_size: f32) {
+    fn add0(&mut self, value: T, config: &QTreeConfig<T::Scalar>, depth: usize) {
         match self {
-            Self::Node { region, children } => {
-                let i = Quadrant::new(region, value.pos()) as usize;
-                children[i].add0(value, target_size);
+            Self::Node {
+                region,
+                children,
+                values,
+            } => {
+                let bounds = value.bounds();
+                let node = &mut children[Quadrant::new(region, bounds.point()) as usize];
+                if node.region().encloses(&bounds) {
+                    node.add0(value, config, depth + 1);
+                } else {
+                    values.push(value);
+                }
             }
             Self::Leaf { region, values } => {
-                if region.w > target_size {
-                    let mut children = Self::blank_children(*region);
-                    let i = Quadrant::new(region, value.pos()) as usize;
-                    children[i].add0(value, target_size);
+                values.push(value);
+                if values.len() > config.capacity && config.can_split(region, depth) {
+                    let region = *region;
+                    let values = std::mem::take(values);
                     *self = Self::Node {
-                        region: *region,
-                        children,
+                        region,
+                        children: Self::blank_children(region),
+                        values: Vec::new(),
+                    };
+                    for value in values {
+                        self.add0(value, config, depth);
                     }
-                } else {
-                    values.push(value);
                 }
             }
         }
     }
 
+    fn collapse(&mut self, config: &QTreeConfig<T::Scalar>) {
+        let Self::Node {
+            region,
+            children,
+            values,
+        } = self
+        else {
+            return;
+        };
+        let mut count = values.len();
+        for node in children.iter() {
+            match node {
+                Self::Node { .. } => return,
+                Self::Leaf { values, .. } => count += values.len(),
+            }
+        }
+        if count <= config.capacity {
+            let mut values = std::mem::take(values);
+            values.extend(
+                std::mem::take(children)
+                    .into_iter()
+                    .flat_map(Self::into_values),
+            );
+            *self = Self::new(*region, values);
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        let region = self.region();
+        if self.values().iter().any(|v| !region.encloses(&v.bounds())) {
+            return Err(format!("value outside its node {region:?}"));
+        }
+        let children = self.children();
+        let quadrants = region.quadrants();
+        let tiled = children.iter().zip(quadrants).all(|(c, q)| c.region() == q);
+        if !self.is_leaf() && (children.len() != 4 || !tiled) {
+            return Err(format!("children do not tile node {region:?}"));
+        }
+        children.iter().try_for_each(Self::check)
+    }
+
+    fn shrink(&mut self, base: Option<&Square<T::Scalar>>) {
+        while let Self::Node {
+            children, values, ..
+        } = self
+        {
+            if !values.is_empty() {
+                break;
+            }
+            let mut filled = children
+                .iter()
+                .enumerate()
+                .filter(|(_, node)| !node.is_empty());
+            let (Some((i, _)), None) = (filled.next(), filled.next()) else {
+                break;
+            };
+            if base.is_some_and(|base| !children[i].region().encloses(base)) {
+                break;
+            }
+            *self = children.swap_remove(i);
+        }
+    }
+
+    fn remove_where0<F: FnMut(&T) -> bool>(
+        &mut self,
+        f: &mut F,
+        config: &QTreeConfig<T::Scalar>,
+        removed: &mut Vec<T>,
+    ) {
+        match self {
+            Self::Node {
+                children, values, ..
+            } => {
+                removed.extend(values.extract_if(.., |v| f(v)));
+                for node in children.iter_mut() {
+                    node.remove_where0(f, config, removed);
+                    node.collapse(config);
+                }
+            }
+            Self::Leaf { values, .. } => removed.extend(values.extract_if(.., |v| f(v))),
+        }
+    }
+
+    fn remove_at0(
+        &mut self,
+        pos: Vector<T::Scalar>,
+        config: &QTreeConfig<T::Scalar>,
+        removed: &mut Vec<T>,
+    ) {
+        match self {
+            Self::Node {
+                region,
+                children,
+                values,
+            } => {
+                removed.extend(values.extract_if(.., |v| v.pos() == pos));
+                let node = &mut children[Quadrant::new(region, pos) as usize];
+                node.remove_at0(pos, config, removed);
+                node.collapse(config);
+            }
+            Self::Leaf { values, .. } => removed.extend(values.extract_if(.., |v| v.pos() == pos)),
+        }
+    }
+
+    fn update0<F: FnMut(&mut T) -> bool>(
+        &mut self,
+        pos: Vector<T::Scalar>,
+        f: &mut F,
+        config: &QTreeConfig<T::Scalar>,
+        moved: &mut Vec<(T, T)>,
+    ) -> usize {
+        let leaf = self.is_leaf();
+        let (Self::Node { region, values, .. } | Self::Leaf { region, values }) = self;
+        let region = *region;
+        let mut count = 0;
+        let mut originals = Vec::new();
+        let leaving: Vec<T> = values
+            .extract_if(.., |v| {
+                if v.pos() != pos {
+                    return false;
+                }
+                let old = v.clone();
+                if !f(v) {
+                    return false;
+                }
+                count += 1;
+                let leaves = !region.holds(&v.bounds(), leaf);
+                if leaves {
+                    originals.push(old);
+                }
+                leaves
+            })
+            .collect();
+        moved.extend(originals.into_iter().zip(leaving));
+        if let Self::Node { children, .. } = self {
+            let node = &mut children[Quadrant::new(&region, pos) as usize];
+            count += node.update0(pos, f, config, moved);
+            node.collapse(config);
+        }
+        count
+    }
+}
+
+impl<T: Clone + Positioned> QTreeMut<T> {
+    pub fn new(region: Square<T::Scalar>, values: Vec<T>) -> Self {
+        Self::with_config(region, values, QTreeConfig::default())
+    }
+
+    pub fn with_config(
+        region: Square<T::Scalar>,
+        values: Vec<T>,
+        config: QTreeConfig<T::Scalar>,
+    ) -> Self {
+        let mut tree = QTreeMut {
+            config,
+            root: QNode::new(region, Vec::new()),
+            base: Some(region),
+        };
+        for value in values {
+            tree.add(value);
+        }
+        tree
+    }
+
+    pub fn config(&self) -> &QTreeConfig<T::Scalar> {
+        &self.config
+    }
+
+    pub fn root(&self) -> &QNode<T> {
+        &self.root
+    }
+
+    pub fn region(&self) -> Square<T::Scalar> {
+        self.root.region()
+    }
+
     pub fn add(&mut self, value: T) {
-        while !self.region().contains(value.pos()) {
-            self.expand_to_contain(value.pos());
+        self.try_add(value).expect("quadtree region out of range")
+    }
+
+    // integer trees fail once the root would have to grow past the scalar's range
+    pub fn try_add(&mut self, value: T) -> Result<(), OutOfRange> {
+        let bounds = value.bounds();
+        if !bounds.is_finite() {
+            return Err(OutOfRange);
+        }
+        while !self.region().encloses(&bounds) {
+            self.root
+                .expand_to_contain(bounds, &self.config)
+                .ok_or(OutOfRange)?;
+        }
+        self.root.add0(value, &self.config, 0);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_empty()
+    }
+
+    pub fn remove_where<F: FnMut(&T) -> bool>(&mut self, mut f: F) -> Vec<T> {
+        let mut removed = Vec::new();
+        self.root.remove_where0(&mut f, &self.config, &mut removed);
+        self.root.shrink(self.base.as_ref());
+        self.root.collapse(&self.config);
+        removed
+    }
+
+    pub fn remove_at(&mut self, pos: Vector<T::Scalar>) -> Vec<T> {
+        let mut removed = Vec::new();
+        if self.region().encloses(&Square::zero(pos)) {
+            self.root.remove_at0(pos, &self.config, &mut removed);
+            self.root.shrink(self.base.as_ref());
+            self.root.collapse(&self.config);
         }
-        let target_size = self.cell_size();
-        self.add0(value, target_size)
+        removed
+    }
+
+    // values moved out of range go back unchanged to where they were
+    fn update<F: FnMut(&mut T) -> bool>(
+        &mut self,
+        pos: Vector<T::Scalar>,
+        mut f: F,
+    ) -> Result<usize, OutOfRange> {
+        if !self.region().encloses(&Square::zero(pos)) {
+            return Ok(0);
+        }
+        let mut moved = Vec::new();
+        let count = self.root.update0(pos, &mut f, &self.config, &mut moved);
+        let mut result = Ok(count);
+        for (old, new) in moved {
+            if self.try_add(new).is_err() {
+                self.root.add0(old, &self.config, 0);
+                result = Err(OutOfRange);
+            }
+        }
+        result
+    }
+
+    pub fn update_at<F: FnMut(&mut T)>(
+        &mut self,
+        pos: Vector<T::Scalar>,
+        mut f: F,
+    ) -> Result<usize, OutOfRange> {
+        self.update(pos, |value| {
+            f(value);
+            true
+        })
+    }
+
+    pub fn relocate(&mut self, from: Vector<T::Scalar>, value: T) -> Result<bool, OutOfRange> {
+        let mut value = Some(value);
+        let count = self.update(from, |old| match value.take() {
+            Some(new) => {
+                *old = new;
+                true
+            }
+            None => false,
+        })?;
+        Ok(count > 0)
     }
 
     pub fn size(&self) -> usize {
-        match self {
-            Self::Node { children, .. } => children.iter().map(Self::size).sum::<usize>() + 1,
-            Self::Leaf { .. } => 1,
+        self.root.size()
+    }
+
+    // for trees that did not come out of this module, such as loaded maps
+    pub fn validate(&self) -> Result<(), String> {
+        let region = self.region();
+        if region.w != region.h {
+            return Err(format!("root region {region:?} is not square"));
         }
+        self.root.check()
     }
 }
@@ -0,0 +1,10 @@
+use super::*;
+
+impl<T: Clone + Positioned> QTreeMut<T> {
+    pub fn query(&self, area: impl Into<Aabb<T::Scalar>>) -> impl Iterator<Item = &T> {
+        let area = area.into();
+        let nodes = self.nodes_where(move |region| region.overlaps(&area));
+        let values = nodes.flat_map(QNode::values);
+        values.filter(move |value| area.overlaps(&value.bounds()))
+    }
+}
@@ -0,0 +1,170 @@
+use super::helpers::*;
+use super::*;
+use std::collections::BinaryHeap;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit<'a, T: Positioned> {
+    pub value: &'a T,
+    pub point: Vector<T::Scalar>,
+    pub normal: Vector<T::Scalar>,
+    pub distance: T::Scalar,
+}
+
+fn ray_rect<S: Float>(rect: &Aabb<S>, origin: [S; 2], dir: [S; 2]) -> Option<(S, [S; 2])> {
+    let (min, max) = ([rect.x, rect.y], [rect.right(), rect.bottom()]);
+    let (mut enter, mut exit) = (-S::INFINITY, S::INFINITY);
+    let mut normal = [S::ZERO; 2];
+    for axis in 0..2 {
+        if dir[axis] == S::ZERO {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let (t1, t2) = (
+            (min[axis] - origin[axis]) / dir[axis],
+            (max[axis] - origin[axis]) / dir[axis],
+        );
+        let (near, far) = (t1.at_most(t2), t1.at_least(t2));
+        if near > enter {
+            enter = near;
+            normal = [S::ZERO; 2];
+            normal[axis] = -dir[axis].signum();
+        }
+        exit = exit.at_most(far);
+    }
+    if enter > exit || exit < S::ZERO {
+        None
+    } else if enter < S::ZERO {
+        Some((S::ZERO, [S::ZERO; 2]))
+    } else {
+        Some((enter, normal))
+    }
+}
+
+fn split<S: Scalar>(v: Vector<S>) -> [S; 2] {
+    let (x, y) = S::xy(v);
+    [x, y]
+}
+
+struct Ray<'a, T: Clone + Positioned> {
+    origin: [T::Scalar; 2],
+    dir: [T::Scalar; 2],
+    max_dist: T::Scalar,
+    heap: BinaryHeap<Candidate<Entry<&'a QNode<T>, RayHit<'a, T>>>>,
+}
+
+impl<'a, T: Clone + Positioned> Ray<'a, T>
+where
+    T::Scalar: Float,
+{
+    fn push_node(&mut self, node: &'a QNode<T>) {
+        if let Some((dist, _)) = ray_rect(&node.region(), self.origin, self.dir)
+            && dist <= self.max_dist
+        {
+            let entry = Entry::Node(node);
+            let dist = dist.to_f64();
+            self.heap.push(Candidate { dist, entry });
+        }
+    }
+
+    fn push_value(&mut self, value: &'a T) {
+        if let Some((distance, [nx, ny])) = ray_rect(&value.bounds(), self.origin, self.dir)
+            && distance <= self.max_dist
+        {
+            let ([ox, oy], [dx, dy]) = (self.origin, self.dir);
+            let hit = RayHit {
+                value,
+                point: T::Scalar::vector(ox + dx * distance, oy + dy * distance),
+                normal: T::Scalar::vector(nx, ny),
+                distance,
+            };
+            let entry = Entry::Value(hit);
+            self.heap.push(Candidate {
+                dist: distance.to_f64(),
+                entry,
+            });
+        }
+    }
+}
+
+impl<'a, T: Clone + Positioned> Iterator for Ray<'a, T>
+where
+    T::Scalar: Float,
+{
+    type Item = RayHit<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.heap.pop()?.entry {
+                Entry::Value(hit) => return Some(hit),
+                Entry::Node(node) => {
+                    for child in node.children() {
+                        self.push_node(child);
+                    }
+                    for value in node.values() {
+                        self.push_value(value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: Clone + Positioned> QTreeMut<T>
+where
+    T::Scalar: Float,
+{
+    pub fn ray_hits(
+        &self,
+        origin: Vector<T::Scalar>,
+        dir: Vector<T::Scalar>,
+        max_dist: T::Scalar,
+    ) -> impl Iterator<Item = RayHit<'_, T>> {
+        let [dx, dy] = split::<T::Scalar>(dir);
+        let length = (dx * dx + dy * dy).sqrt();
+        let mut ray = Ray {
+            origin: split::<T::Scalar>(origin),
+            dir: [T::Scalar::ZERO; 2],
+            max_dist,
+            heap: BinaryHeap::new(),
+        };
+        if length > T::Scalar::ZERO {
+            ray.dir = [dx / length, dy / length];
+            ray.push_node(&self.root);
+        }
+        ray
+    }
+
+    pub fn raycast(
+        &self,
+        origin: Vector<T::Scalar>,
+        dir: Vector<T::Scalar>,
+        max_dist: T::Scalar,
+    ) -> Option<RayHit<'_, T>> {
+        self.ray_hits(origin, dir, max_dist).next()
+    }
+
+    pub fn segment_query(
+        &self,
+        start: Vector<T::Scalar>,
+        end: Vector<T::Scalar>,
+    ) -> Vec<RayHit<'_, T>> {
+        let ([sx, sy], [ex, ey]) = (split::<T::Scalar>(start), split::<T::Scalar>(end));
+        let segment = T::Scalar::vector(ex - sx, ey - sy);
+        let length = ((ex - sx) * (ex - sx) + (ey - sy) * (ey - sy)).sqrt();
+        // a zero-length segment has no direction to cast along, so it hits
+        // whatever holds its point, the same as a ray starting inside a rect
+        if length == T::Scalar::ZERO {
+            let zero = T::Scalar::vector(T::Scalar::ZERO, T::Scalar::ZERO);
+            let hit = |value| RayHit {
+                value,
+                point: start,
+                normal: zero,
+                distance: T::Scalar::ZERO,
+            };
+            return self.query(Aabb::at(start)).map(hit).collect();
+        }
+        self.ray_hits(start, segment, length).collect()
+    }
+}
@@ -0,0 +1,50 @@
+use super::*;
+use macroquad::prelude::{GREEN, Rect, draw_rectangle_lines};
+
+pub trait Draw {
+    fn draw(&self) {}
+}
+
+impl Draw for Vec2 {}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Rect")]
+pub struct RectDef {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+impl From<Rect> for Aabb<f32> {
+    fn from(rect: Rect) -> Self {
+        Aabb::new(rect.x, rect.y, rect.w, rect.h)
+    }
+}
+
+impl From<Aabb<f32>> for Rect {
+    fn from(aabb: Aabb<f32>) -> Self {
+        Rect::new(aabb.x, aabb.y, aabb.w, aabb.h)
+    }
+}
+
+impl From<Rect> for Square {
+    fn from(rect: Rect) -> Self {
+        Square::from(Aabb::from(rect))
+    }
+}
+
+impl<T: Clone + Draw + Positioned<Scalar = f32>> QTreeMut<T> {
+    pub fn draw(&self, scale: f32, world_rect: Rect) {
+        let area = Aabb::from(world_rect);
+        for node in self.nodes_where(|region| region.overlaps(&area)) {
+            if node.is_leaf() {
+                let region = node.region();
+                draw_rectangle_lines(region.x, region.y, region.w, region.h, 2.0 / scale, GREEN);
+            }
+            for v in node.values() {
+                v.draw();
+            }
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use super::*;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Sub};
+
+pub type Vector<S> = <S as Scalar>::Vector;
+
+pub trait Scalar:
+    Copy
+    + Default
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Serialize
+    + DeserializeOwned
+    + Send
+    + Sync
+    + 'static
+{
+    type Vector: Copy + Debug + PartialEq + Send + Sync;
+
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+    const TAG: u8;
+
+    fn vector(x: Self, y: Self) -> Self::Vector;
+    fn xy(v: Self::Vector) -> (Self, Self);
+    fn to_f64(self) -> f64;
+    fn snap(self, step: Self) -> Self;
+
+    fn at_most(self, other: Self) -> Self {
+        if other < self { other } else { self }
+    }
+
+    fn at_least(self, other: Self) -> Self {
+        if other > self { other } else { self }
+    }
+
+    fn is_finite(self) -> bool {
+        true
+    }
+
+    // floats run off to infinity instead of wrapping, so overflow shows up as a
+    // non-finite result; integers override these with their own checked ops
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(self + other).filter(|v| v.is_finite())
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(self - other).filter(|v| v.is_finite())
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        Some(self * other).filter(|v| v.is_finite())
+    }
+
+    fn saturating_add(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn square_size(extent: Self) -> Option<Self> {
+        Some(extent)
+    }
+}
+
+pub trait Float: Scalar + std::ops::Neg<Output = Self> {
+    const INFINITY: Self;
+
+    fn sqrt(self) -> Self;
+    fn signum(self) -> Self;
+}
+
+impl Scalar for f32 {
+    type Vector = Vec2;
+
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    const TWO: Self = 2.0;
+    const TAG: u8 = 0;
+
+    fn vector(x: Self, y: Self) -> Vec2 {
+        vec2(x, y)
+    }
+
+    fn xy(v: Vec2) -> (Self, Self) {
+        (v.x, v.y)
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn snap(self, step: Self) -> Self {
+        (self / step).floor() * step
+    }
+
+    fn is_finite(self) -> bool {
+        f32::is_finite(self)
+    }
+
+    // power-of-two sizes keep every quadrant split exact
+    fn square_size(extent: Self) -> Option<Self> {
+        let size = extent.log2().ceil().exp2();
+        let size = if size < extent { size * 2.0 } else { size };
+        Some(size).filter(|s| s.is_finite())
+    }
+}
+
+impl Float for f32 {
+    const INFINITY: Self = f32::INFINITY;
+
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+
+    fn signum(self) -> Self {
+        f32::signum(self)
+    }
+}
+
+impl Scalar for f64 {
+    type Vector = DVec2;
+
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    const TWO: Self = 2.0;
+    const TAG: u8 = 1;
+
+    fn vector(x: Self, y: Self) -> DVec2 {
+        dvec2(x, y)
+    }
+
+    fn xy(v: DVec2) -> (Self, Self) {
+        (v.x, v.y)
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn snap(self, step: Self) -> Self {
+        (self / step).floor() * step
+    }
+
+    fn is_finite(self) -> bool {
+        f64::is_finite(self)
+    }
+
+    fn square_size(extent: Self) -> Option<Self> {
+        let size = extent.log2().ceil().exp2();
+        let size = if size < extent { size * 2.0 } else { size };
+        Some(size).filter(|s| s.is_finite())
+    }
+}
+
+impl Float for f64 {
+    const INFINITY: Self = f64::INFINITY;
+
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+
+    fn signum(self) -> Self {
+        f64::signum(self)
+    }
+}
+
+impl Scalar for i32 {
+    type Vector = IVec2;
+
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    const TWO: Self = 2;
+    const TAG: u8 = 2;
+
+    fn vector(x: Self, y: Self) -> IVec2 {
+        ivec2(x, y)
+    }
+
+    fn xy(v: IVec2) -> (Self, Self) {
+        (v.x, v.y)
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn snap(self, step: Self) -> Self {
+        self.div_euclid(step) * step
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        i32::checked_add(self, other)
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        i32::checked_sub(self, other)
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        i32::checked_mul(self, other)
+    }
+
+    fn saturating_add(self, other: Self) -> Self {
+        i32::saturating_add(self, other)
+    }
+
+    // integer cells only halve evenly down to 1 when sized by powers of two,
+    // which caps an i32 region at 2^30 units per side
+    fn square_size(extent: Self) -> Option<Self> {
+        let size = (extent.max(1) as u32).checked_next_power_of_two()?;
+        i32::try_from(size).ok()
+    }
+}
+
+pub fn distance_squared<S: Scalar>(a: Vector<S>, b: Vector<S>) -> f64 {
+    let ((ax, ay), (bx, by)) = (S::xy(a), S::xy(b));
+    let (dx, dy) = (ax.to_f64() - bx.to_f64(), ay.to_f64() - by.to_f64());
+    dx * dx + dy * dy
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(bound = "")]
+pub struct Aabb<S: Scalar = f32> {
+    pub x: S,
+    pub y: S,
+    pub w: S,
+    pub h: S,
+}
+
+impl<S: Scalar> Aabb<S> {
+    pub fn new(x: S, y: S, w: S, h: S) -> Self {
+        Aabb { x, y, w, h }
+    }
+
+    pub fn at(pos: Vector<S>) -> Self {
+        let (x, y) = S::xy(pos);
+        Self::new(x, y, S::ZERO, S::ZERO)
+    }
+
+    pub fn point(&self) -> Vector<S> {
+        S::vector(self.x, self.y)
+    }
+
+    // query areas may reach past the scalar's range, so their edges clamp to it
+    pub fn right(&self) -> S {
+        self.x.saturating_add(self.w)
+    }
+
+    pub fn bottom(&self) -> S {
+        self.y.saturating_add(self.h)
+    }
+
+    pub fn is_finite(&self) -> bool {
+        [self.x, self.y, self.right(), self.bottom()]
+            .into_iter()
+            .all(S::is_finite)
+    }
+
+    pub fn center(&self) -> Vector<S> {
+        S::vector(self.x + self.w / S::TWO, self.y + self.h / S::TWO)
+    }
+
+    pub fn contains(&self, pos: Vector<S>) -> bool {
+        let (x, y) = S::xy(pos);
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.x <= other.right()
+            && self.right() >= other.x
+            && self.y <= other.bottom()
+            && self.bottom() >= other.y
+    }
+
+    pub fn combine_with(self, other: Self) -> Self {
+        let (x, y) = (self.x.at_most(other.x), self.y.at_most(other.y));
+        let w = self.right().at_least(other.right()) - x;
+        let h = self.bottom().at_least(other.bottom()) - y;
+        Self::new(x, y, w, h)
+    }
+}
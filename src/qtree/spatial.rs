@@ -0,0 +1,31 @@
+use super::*;
+
+pub trait SpatialIndex<T: Positioned> {
+    fn insert(&mut self, value: T);
+    fn remove(&mut self, pos: Vector<T::Scalar>) -> Vec<T>;
+    fn query<'a>(&'a self, area: Aabb<T::Scalar>) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a;
+    fn nearest(&self, pos: Vector<T::Scalar>) -> Option<&T>;
+}
+
+impl<T: Clone + Positioned> SpatialIndex<T> for QTreeMut<T> {
+    fn insert(&mut self, value: T) {
+        self.add(value);
+    }
+
+    fn remove(&mut self, pos: Vector<T::Scalar>) -> Vec<T> {
+        self.remove_at(pos)
+    }
+
+    fn query<'a>(&'a self, area: Aabb<T::Scalar>) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        QTreeMut::query(self, area)
+    }
+
+    fn nearest(&self, pos: Vector<T::Scalar>) -> Option<&T> {
+        QTreeMut::nearest(self, pos)
+    }
+}
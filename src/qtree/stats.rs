@@ -0,0 +1,86 @@
+use super::*;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct QTreeStats<S: Scalar = f32> {
+    pub nodes: usize,
+    pub leaves: usize,
+    pub empty_leaves: usize,
+    pub values: usize,
+    pub depth_histogram: Vec<usize>,
+    pub min_leaf_values: usize,
+    pub max_leaf_values: usize,
+    pub avg_leaf_values: f32,
+    pub memory: usize,
+    pub region: Square<S>,
+}
+
+impl<S: Scalar> QTreeStats<S> {
+    pub fn depth(&self) -> usize {
+        self.depth_histogram.len().saturating_sub(1)
+    }
+}
+
+impl<T: Clone + Positioned> Visitor<T> for QTreeStats<T::Scalar> {
+    fn enter(&mut self, node: &QNode<T>, depth: usize) -> bool {
+        let count = node.values().len();
+        if self.depth_histogram.len() <= depth {
+            self.depth_histogram.resize(depth + 1, 0);
+        }
+        self.depth_histogram[depth] += 1;
+        self.nodes += 1;
+        self.values += count;
+        self.memory += size_of::<QNode<T>>() + size_of_val(node.values());
+        if node.is_leaf() {
+            self.leaves += 1;
+            self.empty_leaves += (count == 0) as usize;
+            self.min_leaf_values = self.min_leaf_values.min(count);
+            self.max_leaf_values = self.max_leaf_values.max(count);
+            self.avg_leaf_values += count as f32;
+        }
+        true
+    }
+}
+
+impl<S: Scalar> fmt::Display for QTreeStats<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let region = &self.region;
+        writeln!(
+            f,
+            "nodes: {} (leaves {}, empty {})",
+            self.nodes, self.leaves, self.empty_leaves
+        )?;
+        writeln!(f, "depth: {} {:?}", self.depth(), self.depth_histogram)?;
+        writeln!(
+            f,
+            "values: {} (per leaf min {} / avg {:.1} / max {})",
+            self.values, self.min_leaf_values, self.avg_leaf_values, self.max_leaf_values
+        )?;
+        writeln!(f, "memory: ~{:.1} KiB", self.memory as f32 / 1024.0)?;
+        write!(
+            f,
+            "region: ({:?}, {:?}) size {:?}",
+            region.x, region.y, region.w
+        )
+    }
+}
+
+impl<T: Clone + Positioned> QTreeMut<T> {
+    pub fn stats(&self) -> QTreeStats<T::Scalar> {
+        let mut stats = QTreeStats {
+            nodes: 0,
+            leaves: 0,
+            empty_leaves: 0,
+            values: 0,
+            depth_histogram: Vec::new(),
+            min_leaf_values: usize::MAX,
+            max_leaf_values: 0,
+            avg_leaf_values: 0.0,
+            memory: size_of::<Self>() - size_of::<QNode<T>>(),
+            region: self.region(),
+        };
+        self.visit(&mut stats);
+        stats.avg_leaf_values /= stats.leaves as f32;
+        stats
+    }
+}
@@ -0,0 +1,192 @@
+use super::grid::{cell_key, reach};
+use super::*;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ZOrderTree<T: Positioned> {
+    cell: T::Scalar,
+    origin: IVec2,
+    bits: u32,
+    margin: f64,
+    codes: Vec<u64>,
+    values: Vec<T>,
+}
+
+fn spread(v: u32) -> u64 {
+    let mut v = v as u64;
+    v = (v | (v << 16)) & 0x0000_FFFF_0000_FFFF;
+    v = (v | (v << 8)) & 0x00FF_00FF_00FF_00FF;
+    v = (v | (v << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+    (v | (v << 1)) & 0x5555_5555_5555_5555
+}
+
+pub fn morton(x: u32, y: u32) -> u64 {
+    spread(x) | (spread(y) << 1)
+}
+
+// collects the Z-order intervals covering the cells in [lo, hi], merging neighbours
+// and skipping quadrants that hold no codes
+fn split(
+    codes: &[u64],
+    corner: [u64; 2],
+    level: u32,
+    [lo, hi]: [[u64; 2]; 2],
+    out: &mut Vec<(u64, u64)>,
+) {
+    let size = 1u64 << level;
+    let far = [corner[0] + size - 1, corner[1] + size - 1];
+    if far[0] < lo[0] || corner[0] > hi[0] || far[1] < lo[1] || corner[1] > hi[1] {
+        return;
+    }
+    let start = morton(corner[0] as u32, corner[1] as u32);
+    let span = if level == 0 {
+        0
+    } else {
+        u64::MAX >> (64 - 2 * level)
+    };
+    let first = codes.partition_point(|&code| code < start);
+    if codes.get(first).is_none_or(|&code| code > start + span) {
+        return;
+    }
+    if lo[0] <= corner[0] && far[0] <= hi[0] && lo[1] <= corner[1] && far[1] <= hi[1] {
+        match out.last_mut() {
+            Some(last) if last.1 + 1 == start => last.1 = start + span,
+            _ => out.push((start, start + span)),
+        }
+        return;
+    }
+    let half = size / 2;
+    for [dx, dy] in [[0, 0], [half, 0], [0, half], [half, half]] {
+        let corner = [corner[0] + dx, corner[1] + dy];
+        split(codes, corner, level - 1, [lo, hi], out);
+    }
+}
+
+impl<T: Positioned> ZOrderTree<T> {
+    pub fn from_values<I: IntoIterator<Item = T>>(values: I, cell: T::Scalar) -> Self {
+        let values: Vec<T> = values.into_iter().collect();
+        let keys: Vec<IVec2> = values.iter().map(|v| v.coords(cell)).collect();
+        let origin = keys.iter().copied().reduce(IVec2::min).unwrap_or_default();
+        let offset = |key: IVec2| {
+            let (x, y) = (key.x.wrapping_sub(origin.x), key.y.wrapping_sub(origin.y));
+            (x as u32, y as u32)
+        };
+        let extent = keys.iter().map(|&k| {
+            let (x, y) = offset(k);
+            x.max(y)
+        });
+        let bits = 32 - extent.max().unwrap_or(0).leading_zeros();
+        let margin = values.iter().map(reach).fold(0.0, f64::max);
+
+        let mut entries: Vec<(u64, T)> = keys
+            .into_iter()
+            .zip(values)
+            .map(|(key, value)| {
+                let (x, y) = offset(key);
+                (morton(x, y), value)
+            })
+            .collect();
+        entries.sort_by_key(|(code, _)| *code);
+        let (codes, values) = entries.into_iter().unzip();
+        ZOrderTree {
+            cell,
+            origin,
+            bits,
+            margin,
+            codes,
+            values,
+        }
+    }
+
+    pub fn cell(&self) -> T::Scalar {
+        self.cell
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn codes(&self) -> &[u64] {
+        &self.codes
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    fn clamp(&self, lo: IVec2, hi: IVec2) -> Option<([u64; 2], [u64; 2])> {
+        let max = (1i64 << self.bits) - 1;
+        let lo = (lo.as_i64vec2() - self.origin.as_i64vec2()).max(I64Vec2::ZERO);
+        let hi = (hi.as_i64vec2() - self.origin.as_i64vec2()).min(I64Vec2::splat(max));
+        if self.is_empty() || lo.x > hi.x || lo.y > hi.y {
+            return None;
+        }
+        Some(([lo.x as u64, lo.y as u64], [hi.x as u64, hi.y as u64]))
+    }
+
+    fn covers(&self, lo: IVec2, hi: IVec2) -> bool {
+        let max = (1i64 << self.bits) - 1;
+        let lo = lo.as_i64vec2() - self.origin.as_i64vec2();
+        let hi = hi.as_i64vec2() - self.origin.as_i64vec2();
+        lo.max_element() <= 0 && hi.min_element() >= max
+    }
+
+    fn cells(&self, lo: IVec2, hi: IVec2) -> impl Iterator<Item = &T> {
+        let mut intervals = Vec::new();
+        if let Some((lo, hi)) = self.clamp(lo, hi) {
+            split(&self.codes, [0, 0], self.bits, [lo, hi], &mut intervals);
+        }
+        intervals.into_iter().flat_map(|(start, end)| {
+            let from = self.codes.partition_point(|&code| code < start);
+            let to = self.codes.partition_point(|&code| code <= end);
+            &self.values[from..to]
+        })
+    }
+
+    pub fn query(&self, area: impl Into<Aabb<T::Scalar>>) -> impl Iterator<Item = &T> {
+        let area = area.into();
+        let (cell, margin) = (self.cell.to_f64(), self.margin);
+        let lo = cell_key(cell, area.x.to_f64() - margin, area.y.to_f64() - margin);
+        let (right, bottom) = (area.right().to_f64(), area.bottom().to_f64());
+        let hi = cell_key(cell, right + margin, bottom + margin);
+        let values = self.cells(lo, hi);
+        values.filter(move |value| area.overlaps(&value.bounds()))
+    }
+
+    pub fn k_nearest(&self, pos: Vector<T::Scalar>, k: usize) -> Vec<&T> {
+        let (x, y) = T::Scalar::xy(pos);
+        let (x, y, cell) = (x.to_f64(), y.to_f64(), self.cell.to_f64());
+        let mut half = cell;
+        loop {
+            let lo = cell_key(cell, x - half, y - half);
+            let hi = cell_key(cell, x + half, y + half);
+            let found = self.cells(lo, hi);
+            let mut found: Vec<(f64, &T)> = found
+                .map(|v| (distance_squared::<T::Scalar>(v.pos(), pos), v))
+                .collect();
+            found.sort_by(|a, b| a.0.total_cmp(&b.0));
+            // anything closer than `half` has its cell inside the searched square
+            let settled =
+                k == 0 || self.is_empty() || found.len() >= k && found[k - 1].0 <= half * half;
+            if settled || self.covers(lo, hi) {
+                return found.into_iter().take(k).map(|(_, v)| v).collect();
+            }
+            half *= 2.0;
+        }
+    }
+
+    pub fn nearest(&self, pos: Vector<T::Scalar>) -> Option<&T> {
+        self.k_nearest(pos, 1).into_iter().next()
+    }
+}
+
+impl<T: Clone + Positioned> From<QTreeMut<T>> for ZOrderTree<T> {
+    fn from(tree: QTreeMut<T>) -> Self {
+        let cell = tree.config().min_size;
+        Self::from_values(tree, cell)
+    }
+}
@@ -0,0 +1,119 @@
+pub const HIT_STUN: f32 = 0.6;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlayerState {
+    Sit,
+    Hold,
+    Idle,
+    Walk,
+    Hit,
+    Raise,
+}
+
+impl PlayerState {
+    pub const ALL: [PlayerState; 6] = [
+        PlayerState::Sit,
+        PlayerState::Hold,
+        PlayerState::Idle,
+        PlayerState::Walk,
+        PlayerState::Hit,
+        PlayerState::Raise,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PlayerState::Sit => "sit",
+            PlayerState::Hold => "hold",
+            PlayerState::Idle => "idle",
+            PlayerState::Walk => "walk",
+            PlayerState::Hit => "hit",
+            PlayerState::Raise => "raise",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Signals {
+    pub moving: bool,
+    pub crouching: bool,
+    pub carrying: bool,
+    pub on_ground: bool,
+    // the current one-shot clip played to its end this frame
+    pub clip_finished: bool,
+}
+
+pub trait StateHooks {
+    fn enter(&mut self, _state: PlayerState) {}
+    fn exit(&mut self, _state: PlayerState) {}
+}
+
+impl StateHooks for () {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateMachine {
+    state: PlayerState,
+    elapsed: f32,
+    stun: f32,
+    pending_stun: Option<f32>,
+}
+
+impl StateMachine {
+    pub fn new(state: PlayerState) -> Self {
+        StateMachine {
+            state,
+            elapsed: 0.0,
+            stun: 0.0,
+            pending_stun: None,
+        }
+    }
+
+    pub fn state(&self) -> PlayerState {
+        self.state
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn hurt(&mut self, stun: f32) {
+        self.pending_stun = Some(stun);
+    }
+
+    fn next(&self, signals: &Signals) -> PlayerState {
+        use PlayerState::*;
+
+        if self.pending_stun.is_some() {
+            return Hit;
+        }
+        match self.state {
+            Hit if self.elapsed < self.stun => return Hit,
+            Raise if signals.carrying && !signals.clip_finished => return Raise,
+            Raise | Hold if signals.carrying => return Hold,
+            _ => {}
+        }
+        if signals.carrying {
+            Raise
+        } else if signals.crouching && signals.on_ground {
+            Sit
+        } else if signals.moving {
+            Walk
+        } else {
+            Idle
+        }
+    }
+
+    pub fn update(&mut self, dt: f32, signals: &Signals, hooks: &mut impl StateHooks) -> bool {
+        self.elapsed += dt;
+        let next = self.next(signals);
+        let stun = self.pending_stun.take();
+        if next == self.state && stun.is_none() {
+            return false;
+        }
+        hooks.exit(self.state);
+        self.state = next;
+        self.elapsed = 0.0;
+        self.stun = stun.unwrap_or(0.0);
+        hooks.enter(next);
+        true
+    }
+}
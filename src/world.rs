@@ -68,44 +68,32 @@ impl Screen {
     }
 }
 
-impl<T: Clone + Positioned> QTreeMut<T> {
-    pub fn draw(&self, scale: f32, world_rect: Rect) {
-        match self {
-            QTreeMut::Node { children, .. } => {
-                for node in children {
-                    node.draw(scale, world_rect);
-                }
-            }
-            QTreeMut::Leaf { region, values } => {
-                if region.intersect(world_rect).is_some() {
-                    draw_rectangle_lines(
-                        region.x,
-                        region.y,
-                        region.w,
-                        region.h,
-                        2.0 / scale,
-                        GREEN,
-                    );
-                    for v in values {
-                        v.draw();
-                    }
-                }
-            }
-        }
+impl Default for Screen {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Item {
     pos: Vec2,
+    #[serde(with = "RectDef")]
     pub rect: Rect,
 }
 
 impl Positioned for Item {
+    type Scalar = f32;
+
     fn pos(&self) -> Vec2 {
         self.pos
     }
 
+    fn bounds(&self) -> Aabb {
+        self.rect.into()
+    }
+}
+
+impl Draw for Item {
     fn draw(&self) {
         draw_rectangle(
             self.rect.x,
@@ -124,6 +112,36 @@ impl Item {
             rect: Rect::new(pos.x, pos.y, 0.0, 0.0),
         }
     }
+
+    pub fn corners(&self) -> [Vec2; 4] {
+        let Rect { x, y, w, h } = self.rect;
+        [
+            vec2(x, y),
+            vec2(x + w, y),
+            vec2(x, y + h),
+            vec2(x + w, y + h),
+        ]
+    }
+}
+
+impl QTreeMut<Item> {
+    pub fn closest_corner(&self, pos: Vec2) -> Option<Vec2> {
+        let region = self.region();
+        let mut reach = GRID;
+        loop {
+            let area = Square::new(pos.x - reach, pos.y - reach, reach * 2.0);
+            let corners = self.query(*area).flat_map(Item::corners);
+            let closest = corners
+                .map(|corner| (corner.distance_squared(pos), corner))
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            // a closer corner would sit inside the box, so its item overlaps it
+            match closest {
+                Some((dist, corner)) if dist <= reach * reach => return Some(corner),
+                _ if area.encloses(&region) => return closest.map(|(_, corner)| corner),
+                _ => reach *= 2.0,
+            }
+        }
+    }
 }
 
 impl From<Rect> for Item {
@@ -167,14 +185,14 @@ impl<const N: usize, T: Copy> RingBuffer2D<N, T> {
     }
 
     pub fn shift_left(&mut self, new_col: [T; N]) {
-        for row in 0..N {
-            Self::shift_backward(&mut self.values[row], new_col[row]);
+        for (row, new) in self.values.iter_mut().zip(new_col) {
+            Self::shift_backward(row, new);
         }
     }
 
     pub fn shift_right(&mut self, new_col: [T; N]) {
-        for row in 0..N {
-            Self::shift_forward(&mut self.values[row], new_col[row]);
+        for (row, new) in self.values.iter_mut().zip(new_col) {
+            Self::shift_forward(row, new);
         }
     }
 
@@ -0,0 +1,184 @@
+use hello_rustaceans::animation::*;
+
+const PLAYER: &str = include_str!("../resources/player.json");
+
+fn set(json: &str) -> AnimationSet {
+    AnimationSet::from_json(json).unwrap()
+}
+
+fn frames(set: &AnimationSet, clip: &str, steps: usize) -> Vec<usize> {
+    let mut animation = Animation::new(clip);
+    (0..steps)
+        .map(|_| {
+            let frame = animation.frame();
+            animation.step(set);
+            frame
+        })
+        .collect()
+}
+
+const STRIP: &str = r#"{
+    "image": "strip.png",
+    "clips": {
+        "spin": {
+            "mode": "ping_pong",
+            "frames": [
+                { "rect": { "x": 0.0, "y": 0.0, "w": 8.0, "h": 8.0 } },
+                { "rect": { "x": 8.0, "y": 0.0, "w": 8.0, "h": 8.0 }, "duration": 0.1 },
+                { "rect": { "x": 16.0, "y": 0.0, "w": 8.0, "h": 8.0 } }
+            ]
+        },
+        "pop": { "mode": "once", "frames": [
+            { "rect": { "x": 0.0, "y": 8.0, "w": 8.0, "h": 8.0 } },
+            { "rect": { "x": 8.0, "y": 8.0, "w": 8.0, "h": 8.0 } }
+        ] },
+        "spin_back": { "mirror": "spin" }
+    }
+}"#;
+
+#[test]
+fn player_descriptor_loads() {
+    let set = set(PLAYER);
+    assert_eq!(set.scale, 4.0);
+    assert_eq!(set.clips.len(), 24);
+    let left = set.clip("walk_left").unwrap();
+    let right = set.clip("walk_right").unwrap();
+    assert!(left.flip_x && !right.flip_x);
+    assert_eq!(left.frames, right.frames);
+    assert_eq!(set.clip("raise_down").unwrap().mode, LoopMode::Once);
+}
+
+#[test]
+fn defaults_and_mirrors_resolve() {
+    let set = set(STRIP);
+    assert_eq!(set.scale, 1.0);
+    let spin = set.clip("spin").unwrap();
+    assert_eq!(spin.frame_duration(0), 0.5);
+    assert_eq!(spin.frame_duration(1), 0.1);
+    let back = set.clip("spin_back").unwrap();
+    assert_eq!(
+        (back.frames.len(), back.mode, back.flip_x),
+        (3, LoopMode::PingPong, true)
+    );
+}
+
+#[test]
+fn modes_step_through_frames() {
+    let set = set(STRIP);
+    assert_eq!(frames(&set, "spin", 7), [0, 1, 2, 1, 0, 1, 2]);
+    assert_eq!(frames(&set, "pop", 4), [0, 1, 1, 1]);
+    assert_eq!(frames(&set, "missing", 2), [0, 0]);
+    assert!(Animation::new("missing").current(&set).is_none());
+}
+
+#[test]
+fn bad_descriptors_are_rejected() {
+    let unknown = r#"{ "image": "a.png", "clips": { "a": { "mirror": "b" } } }"#;
+    assert!(matches!(
+        AnimationSet::from_json(unknown),
+        Err(AnimationError::UnknownClip(name)) if name == "b"
+    ));
+    let empty = r#"{ "image": "a.png", "clips": { "a": { "frames": [] } } }"#;
+    assert!(matches!(
+        AnimationSet::from_json(empty),
+        Err(AnimationError::EmptyClip(name)) if name == "a"
+    ));
+    let frozen = r#"{ "image": "a.png", "clips": { "a": { "duration": 0.0, "frames": [
+        { "rect": { "x": 0.0, "y": 0.0, "w": 8.0, "h": 8.0 } }
+    ] } } }"#;
+    assert!(matches!(
+        AnimationSet::from_json(frozen),
+        Err(AnimationError::Duration(name)) if name == "a"
+    ));
+    assert!(matches!(
+        AnimationSet::from_json("{}"),
+        Err(AnimationError::Json(_))
+    ));
+}
+
+#[test]
+fn deserializing_resolves_clips() {
+    let empty = r#"{ "image": "a.png", "clips": { "a": { "frames": [] } } }"#;
+    assert!(serde_json::from_str::<AnimationSet>(empty).is_err());
+
+    let player: AnimationSet = serde_json::from_str(PLAYER).unwrap();
+    assert_eq!(player, set(PLAYER));
+    let json = serde_json::to_string(&player).unwrap();
+    assert_eq!(serde_json::from_str::<AnimationSet>(&json).unwrap(), player);
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn player_needs_every_state_and_direction() {
+    use hello_rustaceans::player::*;
+    use hello_rustaceans::state::*;
+
+    let mut player = set(PLAYER);
+    assert!(check_clips(&player).is_ok());
+    player.clips.remove("raise_left");
+    assert!(matches!(
+        check_clips(&player),
+        Err(AnimationError::MissingClip(name)) if name == clip_name(PlayerState::Raise, Dir::Left)
+    ));
+}
+
+const CLOCK: &str = r#"{
+    "image": "clock.png",
+    "clips": {
+        "tick": {
+            "duration": 0.25,
+            "frames": [
+                { "rect": { "x": 0.0, "y": 0.0, "w": 8.0, "h": 8.0 } },
+                { "rect": { "x": 8.0, "y": 0.0, "w": 8.0, "h": 8.0 }, "duration": 0.5 }
+            ]
+        },
+        "pop": { "mode": "once", "frames": [
+            { "rect": { "x": 0.0, "y": 8.0, "w": 8.0, "h": 8.0 } },
+            { "rect": { "x": 8.0, "y": 8.0, "w": 8.0, "h": 8.0 } }
+        ] }
+    }
+}"#;
+
+#[test]
+fn time_advances_by_frame_duration() {
+    let set = set(CLOCK);
+    let mut tick = Animation::new("tick");
+    assert_eq!(tick.update(0.125, &set), None);
+    assert_eq!(tick.frame(), 0);
+    assert_eq!(tick.update(0.125, &set), None);
+    assert_eq!(tick.frame(), 1);
+    assert_eq!(tick.update(0.25, &set), None);
+    assert_eq!(tick.frame(), 1);
+    assert_eq!(tick.update(0.25, &set), Some(AnimationEvent::Looped));
+    assert_eq!(tick.frame(), 0);
+
+    // one long frame lands where many short ones do
+    let (mut slow, mut fast) = (Animation::new("tick"), Animation::new("tick"));
+    assert_eq!(slow.update(1.0, &set), Some(AnimationEvent::Looped));
+    let events: Vec<_> = (0..16).filter_map(|_| fast.update(0.0625, &set)).collect();
+    assert_eq!(events, [AnimationEvent::Looped]);
+    assert_eq!((slow.frame(), fast.frame()), (1, 1));
+}
+
+#[test]
+fn once_clips_finish_exactly_once() {
+    let set = set(CLOCK);
+    let mut pop = Animation::new("pop");
+    assert_eq!(pop.update(0.5, &set), None);
+    assert_eq!(pop.update(0.5, &set), Some(AnimationEvent::Finished));
+    assert!(pop.is_finished());
+    assert_eq!(pop.update(10.0, &set), None);
+    assert_eq!(pop.frame(), 1);
+
+    let mut pop = Animation::new("pop");
+    assert_eq!(pop.update(5.0, &set), Some(AnimationEvent::Finished));
+    assert_eq!(pop.frame(), 1);
+}
+
+#[test]
+fn ping_pong_loops_on_return() {
+    let set = set(STRIP);
+    let mut spin = Animation::new("spin");
+    let events: Vec<_> = (0..4).map(|_| spin.step(&set)).collect();
+    assert_eq!(events, [None, None, None, Some(AnimationEvent::Looped)]);
+}
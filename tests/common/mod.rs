@@ -0,0 +1,24 @@
+// each test crate pulls in only the fixtures it needs
+#![allow(dead_code)]
+
+use hello_rustaceans::qtree::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Boxed(pub Aabb);
+
+impl Positioned for Boxed {
+    type Scalar = f32;
+
+    fn pos(&self) -> Vec2 {
+        self.0.center()
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.0
+    }
+}
+
+// stepping by large primes scatters consecutive indices over [0, w) x [0, h)
+pub fn scattered(count: i32, w: i32, h: i32) -> impl Iterator<Item = Vec2> {
+    (0..count).map(move |i| vec2(((i * 7919) % w) as f32, ((i * 104729) % h) as f32))
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "render")]
+
+use hello_rustaceans::map::*;
+use hello_rustaceans::qtree::*;
+use hello_rustaceans::world::*;
+
+fn level() -> QTreeMut<Item> {
+    let mut tree = QTreeMut::new(
+        Rect::new(-CELL / 2.0, -CELL / 2.0, CELL, CELL).into(),
+        vec![],
+    );
+    for i in 0..20 {
+        let x = (i * 37 % 23) as f32 * GRID;
+        tree.add(Rect::new(x, i as f32 * GRID, GRID * 2.0, GRID).into());
+    }
+    tree.add(Item::new(vec2(1.5, -2.5)));
+    tree
+}
+
+fn tree_json(tree: &QTreeMut<Item>) -> String {
+    serde_json::to_string(tree).unwrap()
+}
+
+fn level_file() -> String {
+    MapFile::new(MapMeta::default(), level()).to_json().unwrap()
+}
+
+#[test]
+fn bare_tree_migrates_to_current_version() {
+    let tree = level();
+    let map = MapFile::from_json(&tree_json(&tree)).unwrap();
+    assert_eq!(map.version, MAP_VERSION);
+    assert_eq!(map.meta, MapMeta::default());
+    assert_eq!(tree_json(&map.tree), tree_json(&tree));
+}
+
+#[test]
+fn map_round_trips_through_json() {
+    let meta = MapMeta {
+        name: String::from("caves"),
+        author: String::from("ferris"),
+        ..Default::default()
+    };
+    let map = MapFile::new(meta.clone(), level());
+    let loaded = MapFile::from_json(&map.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.version, MAP_VERSION);
+    assert_eq!(loaded.meta, meta);
+    assert_eq!(tree_json(&loaded.tree), tree_json(&map.tree));
+}
+
+#[test]
+fn newer_versions_are_rejected() {
+    let mut doc: serde_json::Value = serde_json::from_str(&level_file()).unwrap();
+    doc["version"] = (MAP_VERSION + 1).into();
+    assert!(matches!(
+        MapFile::from_json(&doc.to_string()),
+        Err(MapError::Version(v)) if v == MAP_VERSION + 1
+    ));
+}
+
+#[test]
+fn malformed_versions_are_rejected() {
+    let mut doc: serde_json::Value = serde_json::from_str(&level_file()).unwrap();
+    for version in [
+        serde_json::json!("1"),
+        serde_json::json!(1.5),
+        serde_json::json!(-1),
+        serde_json::json!(u64::from(u32::MAX) + 1),
+        serde_json::Value::Null,
+    ] {
+        doc["version"] = version.clone();
+        assert!(
+            matches!(MapFile::from_json(&doc.to_string()), Err(MapError::BadVersion(v)) if v == version),
+            "{version}"
+        );
+    }
+}
+
+#[test]
+fn malformed_trees_are_rejected() {
+    let doc: serde_json::Value = serde_json::from_str(&level_file()).unwrap();
+    let mut missing = doc.clone();
+    let children = &mut missing["tree"]["root"]["Node"]["children"];
+    children.as_array_mut().unwrap().pop();
+    let mut shifted = doc.clone();
+    shifted["tree"]["root"]["Node"]["children"][1]["Leaf"]["region"]["x"] = 300.0.into();
+    let mut outside = doc.clone();
+    let value = &mut outside["tree"]["root"]["Node"]["children"][1]["Leaf"]["values"][0];
+    value["rect"]["x"] = (-1000.0).into();
+    let mut bare = missing["tree"].clone();
+    bare.as_object_mut().unwrap().remove("base");
+
+    for doc in [missing, shifted, outside, bare] {
+        assert!(
+            matches!(MapFile::from_json(&doc.to_string()), Err(MapError::Tree(_))),
+            "{doc}"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "rayon")]
+
+use hello_rustaceans::qtree::*;
+
+mod common;
+use common::*;
+
+fn points() -> Vec<Vec2> {
+    scattered(2000, 1013, 997)
+        .map(|p| vec2(p.x - 500.0, p.y * 0.5))
+        .collect()
+}
+
+fn areas() -> Vec<Aabb> {
+    (0..64)
+        .map(|i| {
+            let (x, y) = (
+                (i * 37 % 61) as f32 * 20.0 - 600.0,
+                (i * 13 % 29) as f32 * 20.0,
+            );
+            Aabb::new(x, y, 40.0 + i as f32, 25.0)
+        })
+        .collect()
+}
+
+fn targets() -> Vec<Vec2> {
+    (0..64)
+        .map(|i| {
+            vec2(
+                (i * 53 % 71) as f32 * 17.0 - 700.0,
+                (i * 29 % 41) as f32 * 15.0,
+            )
+        })
+        .collect()
+}
+
+macro_rules! matches_sequential {
+    ($tree:expr) => {{
+        let tree = $tree;
+        let (areas, targets) = (areas(), targets());
+
+        let queried: Vec<Vec<&Vec2>> = areas.iter().map(|&a| tree.query(a).collect()).collect();
+        assert_eq!(tree.par_query_many(&areas), queried);
+
+        let nearest: Vec<_> = targets.iter().map(|&p| tree.nearest(p)).collect();
+        assert_eq!(tree.par_nearest_many(&targets), nearest);
+
+        let k_nearest: Vec<_> = targets.iter().map(|&p| tree.k_nearest(p, 7)).collect();
+        assert_eq!(tree.par_k_nearest_many(&targets, 7), k_nearest);
+    }};
+}
+
+#[test]
+fn batches_match_sequential_queries() {
+    let tree = QTreeMut::from_values(points());
+    matches_sequential!(&tree);
+    matches_sequential!(&QTree::from(tree.clone()));
+    matches_sequential!(&ZOrderTree::from(tree));
+}
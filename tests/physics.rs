@@ -0,0 +1,84 @@
+use hello_rustaceans::physics::*;
+use hello_rustaceans::qtree::*;
+
+mod common;
+use common::*;
+
+const DT: f32 = 1.0 / 60.0;
+
+fn level() -> QTreeMut<Boxed> {
+    let solids = [
+        Aabb::new(-1000.0, 0.0, 2000.0, 32.0),
+        Aabb::new(200.0, -200.0, 32.0, 200.0),
+        Aabb::new(-400.0, -1000.0, 200.0, 4.0),
+    ];
+    QTreeMut::from_values(solids.map(Boxed))
+}
+
+fn run(body: &mut Body, world: &QTreeMut<Boxed>, frames: usize, input: impl Fn(usize) -> Input) {
+    let config = PhysicsConfig::default();
+    for frame in 0..frames {
+        body.step(DT, &input(frame), &config, world);
+    }
+}
+
+#[test]
+fn falls_and_rests_on_the_floor() {
+    let world = level();
+    let mut body = Body::new(vec2(0.0, -300.0), vec2(32.0, 48.0));
+    run(&mut body, &world, 120, |_| Input::default());
+    assert!(body.on_ground);
+    assert_eq!(body.vel, Vec2::ZERO);
+    assert!(body.bounds().bottom().abs() < 0.01);
+}
+
+#[test]
+fn walls_stop_horizontal_motion() {
+    let world = level();
+    let mut body = Body::new(vec2(0.0, -24.0), vec2(32.0, 48.0));
+    let right = Input {
+        move_x: 1.0,
+        ..Default::default()
+    };
+    run(&mut body, &world, 180, |_| right);
+    assert!(body.bounds().right() <= 200.0);
+    assert!(body.bounds().right() > 199.0);
+    assert_eq!(body.vel.x, 0.0);
+}
+
+#[test]
+fn holding_jump_goes_higher() {
+    let world = level();
+    let apex = |hold: usize| {
+        let mut body = Body::new(vec2(-600.0, -24.0), vec2(32.0, 48.0));
+        run(&mut body, &world, 2, |_| Input::default());
+        let mut top = body.pos.y;
+        let config = PhysicsConfig::default();
+        for frame in 0..120 {
+            let input = Input {
+                jump_pressed: frame == 0,
+                jump_held: frame < hold,
+                ..Default::default()
+            };
+            body.step(DT, &input, &config, &world);
+            top = top.min(body.pos.y);
+        }
+        assert!(body.on_ground);
+        top
+    };
+    assert!(apex(60) < apex(4) - 50.0);
+}
+
+#[test]
+fn fast_bodies_do_not_tunnel() {
+    let world = level();
+    let mut body = Body::new(vec2(-300.0, -1200.0), vec2(32.0, 48.0));
+    body.vel.y = 1.0e5;
+    let config = PhysicsConfig {
+        max_fall: 1.0e5,
+        ..Default::default()
+    };
+    body.step(DT, &Input::default(), &config, &world);
+    assert!(body.on_ground);
+    assert!(body.bounds().bottom() <= -1000.0);
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "render")]
+
+use hello_rustaceans::qtree::*;
+use hello_rustaceans::world::*;
+
+mod common;
+use common::*;
+
+fn level() -> QTreeMut<Item> {
+    let mut tree = QTreeMut::new(
+        Rect::new(-CELL / 2.0, -CELL / 2.0, CELL, CELL).into(),
+        vec![],
+    );
+    for (i, p) in scattered(500, 997, 613).enumerate() {
+        let pos = (p - vec2(300.0, 0.0)) * GRID;
+        let wh = vec2((i % 5 + 1) as f32 * GRID, (i % 3 + 1) as f32 * GRID);
+        tree.add(Rect::new(pos.x, pos.y, wh.x, wh.y).into());
+    }
+    tree.add(Item::new(vec2(1.5, -2.5)));
+    tree
+}
+
+#[test]
+fn binary_round_trip_matches_json() {
+    let tree = level();
+    let mut bytes = Vec::new();
+    tree.write_binary(&mut bytes).unwrap();
+    let loaded = QTreeMut::<Item>::read_binary(bytes.as_slice()).unwrap();
+
+    let json = serde_json::to_string(&tree).unwrap();
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    assert!(bytes.len() < json.len());
+}
+
+#[test]
+fn binary_reads_into_frozen_tree() {
+    let tree = level();
+    let mut bytes = Vec::new();
+    tree.write_binary(&mut bytes).unwrap();
+    let frozen = QTree::<Item>::read_binary(bytes.as_slice()).unwrap();
+
+    let area = Rect::new(0.0, 0.0, CELL, CELL);
+    assert_eq!(frozen.size(), tree.size());
+    assert_eq!(frozen.query(area).count(), tree.query(area).count());
+}
+
+#[test]
+fn binary_rejects_corruption() {
+    let mut bytes = Vec::new();
+    level().write_binary(&mut bytes).unwrap();
+
+    let mut flipped = bytes.clone();
+    let mid = flipped.len() / 2;
+    flipped[mid] ^= 0xFF;
+    assert!(QTreeMut::<Item>::read_binary(flipped.as_slice()).is_err());
+
+    let truncated = &bytes[..bytes.len() - 1];
+    assert!(QTreeMut::<Item>::read_binary(truncated).is_err());
+
+    let mut version = bytes;
+    version[4] = 0xFF;
+    assert!(QTreeMut::<Item>::read_binary(version.as_slice()).is_err());
+}
+
+fn header(nodes: u32, values: u32) -> Vec<u8> {
+    let mut bytes = b"QTRB".to_vec();
+    bytes.extend(2u16.to_le_bytes());
+    bytes.push(0);
+    bytes.extend(4u32.to_le_bytes());
+    bytes.extend(1f32.to_le_bytes());
+    bytes.extend(16u32.to_le_bytes());
+    bytes.extend(nodes.to_le_bytes());
+    bytes.extend(values.to_le_bytes());
+    bytes
+}
+
+fn node(bytes: &mut Vec<u8>, size: f32, next: u32, leaf: bool) {
+    for s in [0.0, 0.0, size] {
+        bytes.extend(f32::to_le_bytes(s));
+    }
+    bytes.extend(next.to_le_bytes());
+    bytes.push(leaf as u8);
+    bytes.extend([0; 8]);
+}
+
+#[test]
+fn binary_rejects_huge_counts() {
+    let bytes = header(u32::MAX, u32::MAX);
+    assert_eq!(bytes.len(), 27);
+    assert!(QTree::<Vec2>::read_binary(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn binary_rejects_missing_children() {
+    let mut bytes = header(2, 0);
+    node(&mut bytes, 8.0, 2, false);
+    node(&mut bytes, 4.0, 2, true);
+    let checksum = crc32fast::hash(&bytes);
+    bytes.extend(checksum.to_le_bytes());
+
+    let error = QTree::<Vec2>::read_binary(bytes.as_slice()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(QTreeMut::<Vec2>::read_binary(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn deserialize_validates_the_node_table() {
+    let tree = QTree::from(QTreeMut::new(
+        Square::new(0.0, 0.0, 8.0),
+        vec![vec2(1.0, 1.0)],
+    ));
+    let json = serde_json::to_value(&tree).unwrap();
+    let loaded: QTree<Vec2> = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(loaded.nearest(vec2(0.0, 0.0)), Some(&vec2(1.0, 1.0)));
+
+    let mut inner = json.clone();
+    inner["nodes"][0]["leaf"] = false.into();
+    assert!(serde_json::from_value::<QTree<Vec2>>(inner).is_err());
+
+    let mut empty = json.clone();
+    empty["nodes"] = serde_json::json!([]);
+    assert!(serde_json::from_value::<QTree<Vec2>>(empty).is_err());
+
+    let mut range = json;
+    range["nodes"][0]["end"] = 2.into();
+    assert!(serde_json::from_value::<QTree<Vec2>>(range).is_err());
+}
@@ -0,0 +1,225 @@
+use hello_rustaceans::qtree::*;
+use proptest::prelude::*;
+
+mod common;
+use common::*;
+
+// exact binary fractions land on quadrant centres and edges at every depth
+fn coord() -> impl Strategy<Value = f32> {
+    prop_oneof![
+        -100.0f32..100.0,
+        (-64i32..=64).prop_map(|i| i as f32 * 0.5),
+        (0u32..12, any::<bool>())
+            .prop_map(|(e, neg)| if neg { -1.0 } else { 1.0 } * (1 << e) as f32),
+        (-1.0e6f32..1.0e6),
+        // near the top of the range, where growing the root overflows
+        (0.5f32..1.0, any::<bool>()).prop_map(|(f, neg)| if neg { -f } else { f } * f32::MAX),
+    ]
+}
+
+fn point() -> impl Strategy<Value = Vec2> {
+    (coord(), coord()).prop_map(|(x, y)| vec2(x, y))
+}
+
+fn boxed() -> impl Strategy<Value = Boxed> {
+    let size = prop_oneof![
+        Just(0.0f32),
+        0.0f32..50.0,
+        (0u32..7).prop_map(|e| (1 << e) as f32)
+    ];
+    (point(), size.clone(), size).prop_map(|(pos, w, h)| Boxed(Aabb::new(pos.x, pos.y, w, h)))
+}
+
+fn config() -> impl Strategy<Value = QTreeConfig> {
+    (1usize..6, 1usize..12).prop_map(|(capacity, max_depth)| QTreeConfig {
+        capacity,
+        min_size: 1.0,
+        max_depth,
+    })
+}
+
+// returns the tree with the values it accepted
+fn build<T: Clone + Positioned<Scalar = f32>>(
+    values: &[T],
+    config: QTreeConfig,
+) -> (QTreeMut<T>, Vec<T>) {
+    let mut tree = QTreeMut::with_config(Square::new(0.0, 0.0, 64.0), Vec::new(), config);
+    let kept = values
+        .iter()
+        .filter(|value| tree.try_add((*value).clone()).is_ok())
+        .cloned()
+        .collect();
+    (tree, kept)
+}
+
+fn count<T: Clone + Positioned>(node: &QNode<T>) -> usize {
+    1 + node.children().iter().map(count).sum::<usize>()
+}
+
+fn check_tiling<T: Clone + Positioned<Scalar = f32>>(node: &QNode<T>) -> Result<(), TestCaseError> {
+    let region = node.region();
+    prop_assert_eq!(region.w, region.h);
+    if node.is_leaf() {
+        return Ok(());
+    }
+    let children = node.children();
+    prop_assert_eq!(children.len(), 4);
+    let half = region.w / 2.0;
+    let mut corners: Vec<(f32, f32)> = children
+        .iter()
+        .map(|child| {
+            let child = child.region();
+            prop_assert_eq!((child.w, child.h), (half, half));
+            Ok((child.x - region.x, child.y - region.y))
+        })
+        .collect::<Result<_, TestCaseError>>()?;
+    corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    prop_assert_eq!(
+        corners,
+        vec![(0.0, 0.0), (0.0, half), (half, 0.0), (half, half)]
+    );
+    children.iter().try_for_each(check_tiling)
+}
+
+fn check_placement<T: Clone + Positioned<Scalar = f32>>(
+    node: &QNode<T>,
+    root: &Square,
+) -> Result<(), TestCaseError> {
+    let region = node.region();
+    for value in node.values() {
+        let bounds = value.bounds();
+        prop_assert!(
+            region.encloses(&bounds),
+            "{:?} outside {:?}",
+            bounds,
+            region
+        );
+        let (x, y) = (bounds.x, bounds.y);
+        // half-open routing, except on the far edges of the whole tree
+        prop_assert!(x < region.right() || x == root.right());
+        prop_assert!(y < region.bottom() || y == root.bottom());
+        if !node.is_leaf() {
+            let center = region.center();
+            let i = (x >= center.x) as usize + 2 * (y >= center.y) as usize;
+            prop_assert!(!node.children()[i].region().encloses(&bounds));
+        }
+    }
+    node.children()
+        .iter()
+        .try_for_each(|child| check_placement(child, root))
+}
+
+fn check_invariants<T>(tree: &QTreeMut<T>, expected: usize) -> Result<(), TestCaseError>
+where
+    T: Clone + Positioned<Scalar = f32> + PartialEq + std::fmt::Debug + Serialize,
+    T: serde::de::DeserializeOwned,
+{
+    prop_assert_eq!(tree.size(), count(tree.root()));
+    prop_assert_eq!(tree.iter().count(), expected);
+    check_tiling(tree.root())?;
+    check_placement(tree.root(), &tree.region())?;
+
+    let json = serde_json::to_string(tree).unwrap();
+    // roots grown out towards the edge of the f32 range nest deeper than serde_json allows
+    let mut de = serde_json::Deserializer::from_str(&json);
+    de.disable_recursion_limit();
+    let loaded = QTreeMut::<T>::deserialize(&mut de).unwrap();
+    prop_assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    prop_assert_eq!(
+        loaded.iter().collect::<Vec<_>>(),
+        tree.iter().collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn removal_keeps_the_construction_region() {
+    let mut points: Vec<Vec2> = (0..10).map(|i| vec2(1.0 + i as f32 * 0.1, 1.0)).collect();
+    points.push(vec2(60.0, 60.0));
+    let mut tree = QTreeMut::new(Square::new(0.0, 0.0, 64.0), points);
+    assert_eq!(tree.remove_at(vec2(60.0, 60.0)).len(), 1);
+    assert_eq!(tree.region(), Square::new(0.0, 0.0, 64.0));
+
+    tree.add(vec2(500.0, 3.0));
+    assert_eq!(tree.region().w, 512.0);
+    tree.remove_at(vec2(500.0, 3.0));
+    assert_eq!(tree.region(), Square::new(0.0, 0.0, 64.0));
+}
+
+proptest! {
+    #[test]
+    fn points_keep_invariants(points in prop::collection::vec(point(), 0..200), config in config()) {
+        let (tree, kept) = build(&points, config);
+        check_invariants(&tree, kept.len())?;
+    }
+
+    #[test]
+    fn boxes_keep_invariants(boxes in prop::collection::vec(boxed(), 0..120), config in config()) {
+        let (tree, kept) = build(&boxes, config);
+        check_invariants(&tree, kept.len())?;
+    }
+
+    #[test]
+    fn bulk_load_keeps_invariants(boxes in prop::collection::vec(boxed(), 0..200), config in config()) {
+        match QTreeMut::try_from_values_with(boxes.clone(), config) {
+            Ok(tree) => check_invariants(&tree, boxes.len())?,
+            Err(_) => prop_assert!(boxes.iter().any(|b| b.0.point().abs().max_element() > 1.0e38)),
+        }
+    }
+
+    #[test]
+    fn query_matches_brute_force(
+        boxes in prop::collection::vec(boxed(), 0..150),
+        corner in point(),
+        size in (0.0f32..300.0, 0.0f32..300.0),
+    ) {
+        let (tree, boxes) = build(&boxes, QTreeConfig::default());
+        let area = Aabb::new(corner.x, corner.y, size.0, size.1);
+        let mut found: Vec<_> = tree.query(area).map(|b| (b.0.x, b.0.y, b.0.w, b.0.h)).collect();
+        let mut expected: Vec<_> = boxes
+            .iter()
+            .filter(|b| area.overlaps(&b.bounds()))
+            .map(|b| (b.0.x, b.0.y, b.0.w, b.0.h))
+            .collect();
+        found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        prop_assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn removal_keeps_invariants(points in prop::collection::vec(point(), 1..200), config in config(), pick in any::<prop::sample::Index>()) {
+        let (mut tree, points) = build(&points, config);
+        prop_assume!(!points.is_empty());
+        let target = points[pick.index(points.len())];
+        let removed = tree.remove_at(target);
+        let matching = points.iter().filter(|&&p| p == target).count();
+        prop_assert_eq!(removed.len(), matching);
+        check_invariants(&tree, points.len() - matching)?;
+
+        let cut = target.x;
+        let removed = tree.remove_where(|p| p.x < cut);
+        let left = points.iter().filter(|&&p| p != target && p.x >= cut).count();
+        prop_assert!(removed.iter().all(|p| p.x < cut));
+        check_invariants(&tree, left)?;
+        prop_assert!(tree.region().encloses(&Square::new(0.0, 0.0, 64.0)));
+    }
+
+    #[test]
+    fn relocate_keeps_invariants(points in prop::collection::vec(point(), 1..150), config in config(), pick in any::<prop::sample::Index>(), to in point()) {
+        let (mut tree, points) = build(&points, config);
+        prop_assume!(!points.is_empty());
+        let from = points[pick.index(points.len())];
+        let fits = tree.clone().try_add(to).is_ok();
+        match tree.relocate(from, to) {
+            Ok(found) => prop_assert!(found && fits),
+            Err(_) => {
+                prop_assert!(!fits);
+                prop_assert!(tree.query(Aabb::at(from)).any(|&p| p == from));
+            }
+        }
+        check_invariants(&tree, points.len())?;
+        if fits {
+            prop_assert!(tree.query(Aabb::at(to)).any(|&p| p == to));
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use hello_rustaceans::qtree::*;
+
+#[test]
+fn i32_tree_grows_up_to_its_range() {
+    let mut tree = QTreeMut::new(Square::new(0i32, 0, 4), vec![]);
+    assert_eq!(tree.try_add(ivec2(1_000_000_000, 5)), Ok(()));
+    assert_eq!(tree.region().w, 1 << 30);
+
+    assert_eq!(tree.try_add(ivec2(2_000_000_000, 5)), Err(OutOfRange));
+    assert_eq!(tree.try_add(ivec2(-5, 0)), Err(OutOfRange));
+    let everything = Aabb::new(0, 0, i32::MAX, i32::MAX);
+    assert_eq!(tree.query(everything).count(), 1);
+    assert_eq!(tree.nearest(ivec2(0, 0)), Some(&ivec2(1_000_000_000, 5)));
+}
+
+#[test]
+fn i32_bulk_load_rejects_spans_past_the_range() {
+    let far = vec![ivec2(i32::MIN + 5, 0), ivec2(i32::MAX - 5, 0)];
+    let result = QTreeMut::try_from_values_with(far, QTreeConfig::default());
+    assert_eq!(result.err(), Some(OutOfRange));
+
+    let near = vec![ivec2(i32::MAX - 5000, 0), ivec2(i32::MAX - 3000, -7)];
+    let tree = QTreeMut::try_from_values_with(near.clone(), QTreeConfig::default()).unwrap();
+    let region = tree.region();
+    assert!(near.iter().all(|p| region.encloses(&Aabb::at(*p))));
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn add_panics_instead_of_wrapping() {
+    let mut tree = QTreeMut::new(Square::new(0i32, 0, 4), vec![]);
+    tree.add(ivec2(2_000_000_000, 5));
+}
+
+#[test]
+fn i32_queries_reach_the_edges_of_the_range() {
+    let points = vec![ivec2(3, 0), ivec2(12, 0), ivec2(-7, 2)];
+    let tree = QTreeMut::from_values(points.clone());
+    assert_eq!(tree.nearest(ivec2(i32::MIN, 0)), Some(&ivec2(-7, 2)));
+    assert_eq!(tree.nearest(ivec2(i32::MAX, i32::MAX)), Some(&ivec2(12, 0)));
+
+    let wide = Aabb::new(10, 0, i32::MAX, 1);
+    assert_eq!(tree.query(wide).collect::<Vec<_>>(), [&ivec2(12, 0)]);
+    let corner = Aabb::new(-100, -100, i32::MAX, i32::MAX);
+    assert_eq!(tree.query(corner).count(), 3);
+
+    let mut grid = HashGrid::new(4);
+    let zorder = ZOrderTree::from_values(points.clone(), 4);
+    for p in points {
+        grid.insert(p);
+    }
+    assert_eq!(grid.query(wide).collect::<Vec<_>>(), [&ivec2(12, 0)]);
+    assert_eq!(zorder.query(wide).collect::<Vec<_>>(), [&ivec2(12, 0)]);
+}
+
+#[test]
+fn float_trees_reject_values_past_the_range() {
+    let mut tree = QTreeMut::new(Square::new(0.0, 0.0, 64.0), vec![]);
+    assert_eq!(tree.try_add(vec2(-3.0e38, 1.0)), Err(OutOfRange));
+    assert_eq!(tree.try_add(vec2(f32::NAN, 1.0)), Err(OutOfRange));
+    assert_eq!(tree.try_add(vec2(3.0e38, 3.0e38)), Err(OutOfRange));
+    assert_eq!(tree.try_add(vec2(1.0e38, 5.0)), Ok(()));
+    assert!(tree.region().w.is_finite());
+    assert_eq!(tree.iter().count(), 1);
+
+    let far = vec![vec2(-3.0e38, 0.0), vec2(3.0e38, 0.0)];
+    let result = QTreeMut::try_from_values_with(far, QTreeConfig::default());
+    assert_eq!(result.err(), Some(OutOfRange));
+    let mut tree = QTreeMut::new(Square::new(0.0, 0.0, 64.0), vec![vec2(183713.47, 0.0)]);
+    assert_eq!(tree.try_add(vec2(-1.7e38, 0.0)), Err(OutOfRange));
+    assert_eq!(tree.nearest(vec2(0.0, 0.0)), Some(&vec2(183713.47, 0.0)));
+
+    let nan = vec![vec2(0.0, 0.0), vec2(1.0, f32::NAN)];
+    let result = QTreeMut::try_from_values_with(nan, QTreeConfig::default());
+    assert_eq!(result.err(), Some(OutOfRange));
+}
+
+#[test]
+fn relocating_out_of_range_keeps_the_old_value() {
+    let mut tree = QTreeMut::new(Square::new(0i32, 0, 4), vec![ivec2(1, 1), ivec2(3, 2)]);
+    let moved = tree.relocate(ivec2(1, 1), ivec2(2_000_000_000, 0));
+    assert_eq!(moved, Err(OutOfRange));
+    assert_eq!(tree.iter().count(), 2);
+    assert_eq!(tree.nearest(ivec2(0, 0)), Some(&ivec2(1, 1)));
+
+    assert_eq!(tree.relocate(ivec2(1, 1), ivec2(900, 5)), Ok(true));
+    assert_eq!(tree.relocate(ivec2(1, 1), ivec2(2, 2)), Ok(false));
+    let moved = tree.update_at(ivec2(3, 2), |p| p.x = i32::MAX);
+    assert_eq!(moved, Err(OutOfRange));
+    assert_eq!(tree.nearest(ivec2(0, 0)), Some(&ivec2(3, 2)));
+}
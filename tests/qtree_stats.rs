@@ -0,0 +1,53 @@
+use hello_rustaceans::qtree::*;
+
+fn tree() -> QTreeMut<Vec2> {
+    let config = QTreeConfig {
+        capacity: 2,
+        ..Default::default()
+    };
+    let points = [
+        vec2(1.0, 1.0),
+        vec2(2.0, 2.0),
+        vec2(3.0, 3.0),
+        vec2(12.0, 12.0),
+    ];
+    QTreeMut::with_config(Square::new(0.0, 0.0, 16.0), points.to_vec(), config)
+}
+
+// the first three points split the top-left corner down to 2x2 cells:
+// depth 1 and 2 each hold one inner node, the rest are leaves
+#[test]
+fn counts_nodes_and_leaves() {
+    let stats = tree().stats();
+    assert_eq!(stats.nodes, 13);
+    assert_eq!(stats.leaves, 10);
+    assert_eq!(stats.empty_leaves, 7);
+    assert_eq!(stats.values, 4);
+}
+
+#[test]
+fn reports_depth_histogram() {
+    let stats = tree().stats();
+    assert_eq!(stats.depth_histogram, [1, 4, 4, 4]);
+    assert_eq!(stats.depth(), 3);
+}
+
+#[test]
+fn reports_values_per_leaf() {
+    let stats = tree().stats();
+    assert_eq!(stats.min_leaf_values, 0);
+    assert_eq!(stats.max_leaf_values, 2);
+    assert_eq!(stats.avg_leaf_values, 0.4);
+}
+
+#[test]
+fn reports_region() {
+    let stats = tree().stats();
+    assert_eq!(stats.region, Square::new(0.0, 0.0, 16.0));
+    assert!(stats.to_string().contains("region: (0.0, 0.0) size 16.0"));
+
+    let empty = QTreeMut::<Vec2>::new(Square::new(-4.0, -4.0, 8.0), Vec::new()).stats();
+    assert_eq!((empty.nodes, empty.leaves, empty.empty_leaves), (1, 1, 1));
+    assert_eq!(empty.depth_histogram, [1]);
+    assert_eq!(empty.region, Square::new(-4.0, -4.0, 8.0));
+}
@@ -0,0 +1,115 @@
+use hello_rustaceans::qtree::*;
+
+mod common;
+use common::*;
+
+fn tree(boxes: &[Aabb]) -> QTreeMut<Boxed> {
+    let values = boxes.iter().copied().map(Boxed).collect();
+    QTreeMut::new(Square::new(0.0, 0.0, 64.0), values)
+}
+
+#[test]
+fn hits_each_face_with_its_normal() {
+    let tree = tree(&[Aabb::new(10.0, 10.0, 4.0, 4.0)]);
+    let cases = [
+        (
+            vec2(0.0, 12.0),
+            vec2(5.0, 0.0),
+            vec2(10.0, 12.0),
+            vec2(-1.0, 0.0),
+        ),
+        (
+            vec2(30.0, 12.0),
+            vec2(-1.0, 0.0),
+            vec2(14.0, 12.0),
+            vec2(1.0, 0.0),
+        ),
+        (
+            vec2(12.0, 0.0),
+            vec2(0.0, 2.0),
+            vec2(12.0, 10.0),
+            vec2(0.0, -1.0),
+        ),
+        (
+            vec2(12.0, 30.0),
+            vec2(0.0, -1.0),
+            vec2(12.0, 14.0),
+            vec2(0.0, 1.0),
+        ),
+    ];
+    for (origin, dir, point, normal) in cases {
+        let hit = tree.raycast(origin, dir, 100.0).unwrap();
+        assert_eq!((hit.point, hit.normal), (point, normal), "{origin} {dir}");
+        assert_eq!(hit.distance, origin.distance(point));
+    }
+}
+
+#[test]
+fn stops_at_max_dist() {
+    let tree = tree(&[Aabb::new(10.0, 10.0, 4.0, 4.0)]);
+    let (origin, dir) = (vec2(0.0, 12.0), vec2(1.0, 0.0));
+    assert!(tree.raycast(origin, dir, 9.5).is_none());
+    assert_eq!(tree.raycast(origin, dir, 10.0).unwrap().distance, 10.0);
+    assert!(tree.raycast(origin, vec2(-1.0, 0.0), 100.0).is_none());
+    assert!(tree.raycast(origin, Vec2::ZERO, 100.0).is_none());
+}
+
+#[test]
+fn starts_inside_a_rect() {
+    let tree = tree(&[Aabb::new(10.0, 10.0, 4.0, 4.0)]);
+    let hit = tree
+        .raycast(vec2(12.0, 11.0), vec2(1.0, 1.0), 100.0)
+        .unwrap();
+    assert_eq!(hit.distance, 0.0);
+    assert_eq!(hit.normal, Vec2::ZERO);
+    assert_eq!(hit.point, vec2(12.0, 11.0));
+}
+
+#[test]
+fn starts_outside_the_root() {
+    let tree = tree(&[Aabb::new(10.0, 10.0, 4.0, 4.0)]);
+    assert!(!tree.region().contains(vec2(-100.0, 12.0)));
+    let hit = tree
+        .raycast(vec2(-100.0, 12.0), vec2(1.0, 0.0), 1000.0)
+        .unwrap();
+    assert_eq!(hit.distance, 110.0);
+    assert_eq!((hit.point, hit.normal), (vec2(10.0, 12.0), vec2(-1.0, 0.0)));
+}
+
+#[test]
+fn segment_hits_come_in_distance_order() {
+    let tree = tree(&[
+        Aabb::new(30.0, 8.0, 2.0, 8.0),
+        Aabb::new(10.0, 11.0, 2.0, 2.0),
+        Aabb::new(20.0, 0.0, 2.0, 40.0),
+        Aabb::new(20.0, 30.0, 2.0, 2.0),
+    ]);
+    let hits = tree.segment_query(vec2(0.0, 12.0), vec2(50.0, 12.0));
+    let distances: Vec<f32> = hits.iter().map(|hit| hit.distance).collect();
+    assert_eq!(distances, [10.0, 20.0, 30.0]);
+
+    let short = tree.segment_query(vec2(0.0, 12.0), vec2(25.0, 12.0));
+    assert_eq!(short.len(), 2);
+    assert!(
+        tree.segment_query(vec2(0.0, 50.0), vec2(9.0, 50.0))
+            .is_empty()
+    );
+}
+
+#[test]
+fn zero_length_segment_hits_what_holds_its_point() {
+    let tree = tree(&[
+        Aabb::new(10.0, 10.0, 4.0, 4.0),
+        Aabb::new(12.0, 8.0, 8.0, 8.0),
+    ]);
+    let hits = tree.segment_query(vec2(13.0, 11.0), vec2(13.0, 11.0));
+    assert_eq!(hits.len(), 2);
+    for hit in hits {
+        assert_eq!((hit.point, hit.normal), (vec2(13.0, 11.0), Vec2::ZERO));
+        assert_eq!(hit.distance, 0.0);
+    }
+    assert!(
+        tree.segment_query(vec2(5.0, 5.0), vec2(5.0, 5.0))
+            .is_empty()
+    );
+}
@@ -0,0 +1,70 @@
+use hello_rustaceans::qtree::*;
+
+mod common;
+use common::*;
+
+fn points() -> Vec<Vec2> {
+    scattered(400, 613, 499)
+        .map(|p| p - vec2(300.0, 0.0))
+        .collect()
+}
+
+fn exercise<I: SpatialIndex<Vec2>>(mut index: I) {
+    let points = points();
+    for &p in &points {
+        index.insert(p);
+    }
+
+    let area = Aabb::new(-50.0, 100.0, 120.0, 80.0);
+    let mut found: Vec<_> = index.query(area).map(|p| (p.x, p.y)).collect();
+    let mut expected: Vec<_> = points
+        .iter()
+        .filter(|p| area.overlaps(&Aabb::at(**p)))
+        .map(|p| (p.x, p.y))
+        .collect();
+    found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(found, expected);
+
+    let target = vec2(1000.0, -1000.0);
+    let closest = points
+        .iter()
+        .map(|p| p.distance_squared(target))
+        .fold(f32::INFINITY, f32::min);
+    let nearest = index.nearest(target).unwrap();
+    assert_eq!(nearest.distance_squared(target), closest);
+
+    let removed = index.remove(*nearest);
+    assert_eq!(removed.len(), 1);
+    assert!(index.query(Aabb::at(removed[0])).next().is_none());
+    assert!(index.remove(target).is_empty());
+}
+
+#[test]
+fn quadtree_backend() {
+    exercise(QTreeMut::new(Square::new(0.0, 0.0, 16.0), Vec::new()));
+}
+
+#[test]
+fn hash_grid_backend() {
+    exercise(HashGrid::new(32.0));
+}
+
+#[test]
+fn hash_grid_wide_queries_walk_occupied_cells() {
+    let mut grid = HashGrid::new(1.0);
+    for p in points() {
+        grid.insert(Boxed(Aabb::at(p)));
+    }
+    // one huge value widens every later query by its reach
+    grid.insert(Boxed(Aabb::new(0.0, 0.0, 1e6, 1e6)));
+
+    let area = Aabb::new(-1e9, -1e9, 2e9, 2e9);
+    assert_eq!(grid.query(area).count(), points().len() + 1);
+    let area = Aabb::new(-50.0, 100.0, 120.0, 80.0);
+    let expected = points()
+        .iter()
+        .filter(|p| area.overlaps(&Aabb::at(**p)))
+        .count();
+    assert_eq!(grid.query(area).count(), expected + 1);
+}
@@ -0,0 +1,123 @@
+use hello_rustaceans::state::*;
+
+const DT: f32 = 1.0 / 60.0;
+
+#[derive(Default)]
+struct Log(Vec<String>);
+
+impl StateHooks for Log {
+    fn enter(&mut self, state: PlayerState) {
+        self.0.push(format!("enter {state:?}"));
+    }
+
+    fn exit(&mut self, state: PlayerState) {
+        self.0.push(format!("exit {state:?}"));
+    }
+}
+
+fn grounded() -> Signals {
+    Signals {
+        on_ground: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn walks_on_input_and_fires_hooks() {
+    let mut machine = StateMachine::new(PlayerState::Idle);
+    let mut log = Log::default();
+    assert!(!machine.update(DT, &grounded(), &mut log));
+
+    let walking = Signals {
+        moving: true,
+        ..grounded()
+    };
+    assert!(machine.update(DT, &walking, &mut log));
+    assert_eq!(machine.state(), PlayerState::Walk);
+    machine.update(DT, &grounded(), &mut log);
+    assert_eq!(machine.state(), PlayerState::Idle);
+    assert_eq!(
+        log.0,
+        ["exit Idle", "enter Walk", "exit Walk", "enter Idle"]
+    );
+}
+
+#[test]
+fn hit_stun_lasts_its_timer() {
+    let mut machine = StateMachine::new(PlayerState::Walk);
+    machine.hurt(0.5);
+    let walking = Signals {
+        moving: true,
+        ..grounded()
+    };
+    machine.update(DT, &walking, &mut ());
+    assert_eq!(machine.state(), PlayerState::Hit);
+
+    // exact binary steps so the timer boundary is deterministic
+    let frames = (0..60)
+        .take_while(|_| {
+            machine.update(0.125, &walking, &mut ());
+            machine.state() == PlayerState::Hit
+        })
+        .count();
+    assert_eq!(frames, 3);
+    assert_eq!(machine.state(), PlayerState::Walk);
+}
+
+#[test]
+fn hurting_again_restarts_the_stun() {
+    let mut machine = StateMachine::new(PlayerState::Idle);
+    let mut log = Log::default();
+    machine.hurt(0.5);
+    machine.update(DT, &grounded(), &mut log);
+    machine.update(0.4, &grounded(), &mut log);
+    machine.hurt(0.5);
+    machine.update(DT, &grounded(), &mut log);
+    machine.update(0.4, &grounded(), &mut log);
+    assert_eq!(machine.state(), PlayerState::Hit);
+    assert_eq!(log.0[2..], ["exit Hit", "enter Hit"]);
+}
+
+#[test]
+fn carrying_raises_then_holds() {
+    let mut machine = StateMachine::new(PlayerState::Idle);
+    let carrying = Signals {
+        carrying: true,
+        ..grounded()
+    };
+    machine.update(DT, &carrying, &mut ());
+    assert_eq!(machine.state(), PlayerState::Raise);
+    // raising lasts as long as its clip, however long that is
+    machine.update(10.0, &carrying, &mut ());
+    assert_eq!(machine.state(), PlayerState::Raise);
+    let finished = Signals {
+        clip_finished: true,
+        ..carrying
+    };
+    machine.update(DT, &finished, &mut ());
+    assert_eq!(machine.state(), PlayerState::Hold);
+    machine.update(DT, &finished, &mut ());
+    assert_eq!(machine.state(), PlayerState::Hold);
+    machine.update(DT, &grounded(), &mut ());
+    assert_eq!(machine.state(), PlayerState::Idle);
+}
+
+#[test]
+fn crouching_sits_only_on_the_ground() {
+    let mut machine = StateMachine::new(PlayerState::Idle);
+    let crouching = Signals {
+        crouching: true,
+        ..Default::default()
+    };
+    machine.update(DT, &crouching, &mut ());
+    assert_eq!(machine.state(), PlayerState::Idle);
+    machine.update(
+        DT,
+        &Signals {
+            on_ground: true,
+            ..crouching
+        },
+        &mut (),
+    );
+    assert_eq!(machine.state(), PlayerState::Sit);
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "render")]
+
+use hello_rustaceans::qtree::*;
+use hello_rustaceans::world::*;
+
+fn items(rects: &[Rect]) -> QTreeMut<Item> {
+    let region = Rect::new(-CELL / 2.0, -CELL / 2.0, CELL, CELL);
+    let values = rects.iter().map(|&rect| rect.into()).collect();
+    QTreeMut::new(region.into(), values)
+}
+
+#[test]
+fn closest_corner_looks_past_item_centres() {
+    let mut rects = vec![Rect::new(0.0, 0.0, 1000.0, 1000.0)];
+    rects.extend((0..5).map(|i| Rect::new(1020.0 + i as f32 * 3.0, 1020.0, 2.0, 2.0)));
+    let tree = items(&rects);
+    let cursor = vec2(1003.0, 1003.0);
+    assert_eq!(tree.closest_corner(cursor), Some(vec2(1000.0, 1000.0)));
+    assert_eq!(
+        tree.closest_corner(vec2(1021.0, 1019.0)),
+        Some(vec2(1020.0, 1020.0))
+    );
+}
+
+#[test]
+fn closest_corner_searches_the_whole_tree() {
+    let tree = items(&[Rect::new(-3000.0, 2000.0, 10.0, 10.0)]);
+    assert_eq!(
+        tree.closest_corner(vec2(5000.0, -5000.0)),
+        Some(vec2(-2990.0, 2000.0))
+    );
+    assert_eq!(items(&[]).closest_corner(Vec2::ZERO), None);
+}
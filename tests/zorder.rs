@@ -0,0 +1,142 @@
+use hello_rustaceans::qtree::*;
+
+mod common;
+use common::*;
+
+// a fixed LCG keeps the data reproducible without pulling in a rng
+fn noise(seed: &mut u64) -> f32 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*seed >> 40) as f32 / (1u64 << 24) as f32
+}
+
+fn clusters() -> Vec<Vec2> {
+    let centres = [
+        vec2(0.0, 0.0),
+        vec2(5000.0, -3000.0),
+        vec2(-20000.0, 12000.0),
+    ];
+    let mut seed = 7;
+    centres
+        .iter()
+        .flat_map(|&c| (0..150).map(move |_| c))
+        .map(|c| c + vec2(noise(&mut seed), noise(&mut seed)) * 200.0 - 100.0)
+        .collect()
+}
+
+fn areas() -> Vec<Aabb> {
+    vec![
+        Aabb::new(-50.0, -50.0, 100.0, 100.0),
+        Aabb::new(4950.0, -3100.0, 300.0, 80.0),
+        Aabb::new(-30000.0, -5000.0, 40000.0, 20000.0),
+        Aabb::new(-100000.0, -100000.0, 200000.0, 200000.0),
+        Aabb::new(50000.0, 50000.0, 10.0, 10.0),
+        Aabb::new(-20100.0, 11900.0, 0.0, 0.0),
+    ]
+}
+
+fn targets() -> Vec<Vec2> {
+    vec![
+        vec2(3.0, -7.0),
+        vec2(2500.0, -1500.0),
+        vec2(-21000.0, 12500.0),
+        vec2(90000.0, -90000.0),
+        vec2(-1e6, 1e6),
+    ]
+}
+
+fn sorted<'a>(values: impl Iterator<Item = &'a Vec2>) -> Vec<(f32, f32)> {
+    let mut values: Vec<_> = values.map(|p| (p.x, p.y)).collect();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    values
+}
+
+// f64 distances, the same ranking the tree uses
+fn distances<'a>(values: impl IntoIterator<Item = &'a Vec2>, target: Vec2) -> Vec<f64> {
+    values
+        .into_iter()
+        .map(|p| distance_squared::<f32>(*p, target))
+        .collect()
+}
+
+#[test]
+fn query_matches_brute_force() {
+    let points = clusters();
+    for cell in [1.0, 16.0, 500.0] {
+        let tree = ZOrderTree::from_values(points.clone(), cell);
+        for area in areas() {
+            let expected = points.iter().filter(|p| area.overlaps(&Aabb::at(**p)));
+            assert_eq!(sorted(tree.query(area)), sorted(expected), "{area:?}");
+        }
+    }
+}
+
+#[test]
+fn query_reaches_extents_across_cells() {
+    let mut seed = 11;
+    let boxes: Vec<Boxed> = (0..300)
+        .map(|_| {
+            let pos = vec2(noise(&mut seed), noise(&mut seed)) * 4000.0 - 2000.0;
+            let size = vec2(noise(&mut seed), noise(&mut seed)) * 150.0;
+            Boxed(Aabb::new(pos.x, pos.y, size.x, size.y))
+        })
+        .collect();
+    let tree = ZOrderTree::from_values(boxes.clone(), 8.0);
+    for area in areas() {
+        let found = tree.query(area).count();
+        let expected = boxes.iter().filter(|b| area.overlaps(&b.0)).count();
+        assert_eq!(found, expected, "{area:?}");
+    }
+}
+
+#[test]
+fn nearest_matches_brute_force() {
+    let points = clusters();
+    for cell in [1.0, 16.0, 500.0] {
+        let tree = ZOrderTree::from_values(points.clone(), cell);
+        for target in targets() {
+            let mut expected = distances(&points, target);
+            expected.sort_by(f64::total_cmp);
+
+            let nearest = tree.nearest(target).unwrap();
+            assert_eq!(distances([nearest], target), expected[..1], "{target}");
+            for k in [0, 1, 5, 200, 1000] {
+                let found = distances(tree.k_nearest(target, k), target);
+                let want = &expected[..k.min(expected.len())];
+                assert_eq!(found, want, "{target} k={k}");
+            }
+        }
+    }
+}
+
+#[test]
+fn empty_tree_finds_nothing() {
+    let tree = ZOrderTree::<Vec2>::from_values(Vec::new(), 4.0);
+    assert!(tree.nearest(Vec2::ZERO).is_none());
+    assert!(tree.k_nearest(Vec2::ZERO, 3).is_empty());
+    assert_eq!(tree.query(Aabb::new(-1.0, -1.0, 2.0, 2.0)).count(), 0);
+}
+
+// empty quadrants must be skipped, or unaligned boxes cost their perimeter in cells
+#[test]
+fn sparse_worlds_stay_cheap() {
+    let far = 1 << 24;
+    let tree = ZOrderTree::from_values(vec![ivec2(0, 0), ivec2(far, 3)], 1);
+    assert_eq!(tree.nearest(ivec2(far / 2, 7)), Some(&ivec2(far, 3)));
+    assert_eq!(tree.nearest(ivec2(far / 2 - 1, 7)), Some(&ivec2(0, 0)));
+    assert_eq!(
+        tree.k_nearest(ivec2(far - 9, 11), 2),
+        [&ivec2(far, 3), &ivec2(0, 0)]
+    );
+    assert_eq!(tree.query(Aabb::new(3, 5, far / 2, far / 2)).count(), 0);
+    assert_eq!(
+        tree.query(Aabb::new(-far, -far, 3 * far, 3 * far)).count(),
+        2
+    );
+
+    let corners = vec![ivec2(i32::MIN, i32::MIN), ivec2(i32::MAX, i32::MAX)];
+    let tree = ZOrderTree::from_values(corners, 1);
+    assert_eq!(tree.nearest(ivec2(5, 7)), Some(&ivec2(i32::MAX, i32::MAX)));
+    assert_eq!(tree.query(Aabb::new(-1000, 3, 1 << 30, 1 << 30)).count(), 0);
+}